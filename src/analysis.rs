@@ -0,0 +1,555 @@
+use crate::io::{apply_mode, read_front_matter, read_words_from_file, Entry, TextMode};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub enum Language {
+    #[default]
+    Auto,
+    German,
+    English,
+}
+
+impl Language {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::Auto => "Automatisch",
+            Language::German => "Deutsch",
+            Language::English => "Englisch",
+        }
+    }
+}
+
+#[derive(Serialize, PartialEq, Debug, Clone)]
+pub struct Analysis {
+    pub file: String,
+    pub mode: String,
+    pub language: Language,
+    pub words: usize,
+    pub unique_words: usize,
+    pub sentences: usize,
+    pub average_word_length: f32,
+    pub syllables: usize,
+    pub syllables_per_word: f32,
+    pub wpm: f32,
+    pub reading_minutes: f32,
+    // only for texts, a word list has no sentences to measure
+    pub readability: Option<Readability>,
+}
+
+// the indices for a text, the one shown first depends on the language
+#[derive(Serialize, PartialEq, Debug, Clone)]
+pub struct Readability {
+    // Flesch reading ease for German (Amstad), 0 = very hard, 100 = very easy
+    pub amstad: f32,
+    // the original Flesch reading ease for English
+    pub flesch: f32,
+    // Läsbarhetsindex, works for both languages, below 40 is easy
+    pub lix: f32,
+    // first Wiener Sachtextformel, roughly the school year from 4 to 15
+    pub wiener: f32,
+}
+
+impl Readability {
+    pub fn main_score(&self, language: Language) -> (&'static str, f32) {
+        match language {
+            Language::English => ("Flesch", self.flesch),
+            _ => ("Flesch-Amstad", self.amstad),
+        }
+    }
+}
+
+// the usual bands of the Flesch scale, the same for both languages
+fn flesch_label(score: f32) -> &'static str {
+    match score {
+        s if s >= 90.0 => "sehr leicht",
+        s if s >= 80.0 => "leicht",
+        s if s >= 70.0 => "eher leicht",
+        s if s >= 60.0 => "mittel",
+        s if s >= 50.0 => "eher schwer",
+        s if s >= 30.0 => "schwer",
+        _ => "sehr schwer",
+    }
+}
+
+fn lix_label(lix: f32) -> &'static str {
+    match lix {
+        l if l < 25.0 => "Kinderbuch",
+        l if l < 35.0 => "einfach",
+        l if l < 45.0 => "mittel",
+        l if l < 55.0 => "schwer",
+        _ => "sehr schwer",
+    }
+}
+
+const VOWELS: &str = "aeiouäöüyéèàâ";
+
+const GERMAN_WORDS: [&str; 16] = [
+    "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "ich", "zu", "mit", "sich", "auf",
+    "im", "den", "es",
+];
+const ENGLISH_WORDS: [&str; 16] = [
+    "the", "and", "is", "of", "to", "a", "in", "it", "that", "you", "was", "for", "on", "with",
+    "not", "are",
+];
+
+// letters and digits of a token, without the punctuation around it
+fn clean(token: &str) -> &str {
+    token.trim_matches(|c: char| !c.is_alphanumeric())
+}
+
+// vowel groups, so diphthongs like "ei" or "au" count once
+pub fn syllables(word: &str, language: Language) -> usize {
+    let mut count = 0;
+    let mut in_vowel = false;
+    for c in word.chars().flat_map(char::to_lowercase) {
+        let vowel = VOWELS.contains(c);
+        if vowel && !in_vowel {
+            count += 1;
+        }
+        in_vowel = vowel;
+    }
+    // a silent e at the end like in "make", but not in "table"
+    let lower = word.to_lowercase();
+    if language == Language::English
+        && count > 1
+        && lower.ends_with('e')
+        && !lower.ends_with("le")
+        && !lower.ends_with("ee")
+    {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+fn sentences(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_end = false;
+    for c in text.chars() {
+        // "..." or "?!" end one sentence
+        let end = matches!(c, '.' | '!' | '?' | '…');
+        if end && !in_end {
+            count += 1;
+        }
+        in_end = end;
+    }
+    count.max(1)
+}
+
+// common short words decide, umlauts count for German; word lists without any stay German
+pub fn detect_language(words: &[&str]) -> Language {
+    let mut german = 0;
+    let mut english = 0;
+    for word in words {
+        let lower = word.to_lowercase();
+        if GERMAN_WORDS.contains(&lower.as_str()) || lower.contains(['ä', 'ö', 'ü', 'ß']) {
+            german += 1;
+        }
+        if ENGLISH_WORDS.contains(&lower.as_str()) {
+            english += 1;
+        }
+    }
+    if english > german {
+        Language::English
+    } else {
+        Language::German
+    }
+}
+
+pub fn readability(
+    words: usize,
+    sentences: usize,
+    syllable_counts: &[usize],
+    long_words: usize,
+) -> Readability {
+    let word_count = words.max(1) as f32;
+    let words_per_sentence = word_count / sentences.max(1) as f32;
+    let syllables_per_word = syllable_counts.iter().sum::<usize>() as f32 / word_count;
+    let percent = |n: usize| 100.0 * n as f32 / word_count;
+    let polysyllables = percent(syllable_counts.iter().filter(|s| **s >= 3).count());
+    let monosyllables = percent(syllable_counts.iter().filter(|s| **s == 1).count());
+    Readability {
+        amstad: (180.0 - words_per_sentence - 58.5 * syllables_per_word).clamp(0.0, 100.0),
+        flesch: (206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word)
+            .clamp(0.0, 100.0),
+        lix: words_per_sentence + percent(long_words),
+        wiener: 0.1935 * polysyllables + 0.1672 * words_per_sentence + 0.1297 * percent(long_words)
+            - 0.0327 * monosyllables
+            - 0.875,
+    }
+}
+
+pub fn analyze(
+    file: &Path,
+    entries: &[Entry],
+    poem_mode: bool,
+    language: Language,
+    wpm: f32,
+) -> Analysis {
+    let text = entries
+        .iter()
+        .map(|e| e.text.as_str())
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .map(clean)
+        .filter(|w| !w.is_empty())
+        .collect();
+    let language = match language {
+        // single letters like in an alphabet list are no hint, "a" is not the English article there
+        Language::Auto if !poem_mode => detect_language(
+            &words
+                .iter()
+                .copied()
+                .filter(|w| w.chars().count() > 1)
+                .collect::<Vec<&str>>(),
+        ),
+        Language::Auto => detect_language(&words),
+        language => language,
+    };
+    let unique: BTreeSet<String> = words.iter().map(|w| w.to_lowercase()).collect();
+    let letters: usize = words.iter().map(|w| w.chars().count()).sum();
+    let syllable_counts: Vec<usize> = words.iter().map(|w| syllables(w, language)).collect();
+    let syllable_count: usize = syllable_counts.iter().sum();
+    let long_words = words.iter().filter(|w| w.chars().count() > 6).count();
+    // a word list has no sentences, every word stands alone
+    let sentence_count = if poem_mode {
+        sentences(&text)
+    } else {
+        words.len().max(1)
+    };
+
+    let word_count = words.len().max(1) as f32;
+    Analysis {
+        file: file.display().to_string(),
+        mode: if poem_mode { "poem" } else { "words" }.to_string(),
+        language,
+        words: words.len(),
+        unique_words: unique.len(),
+        sentences: sentence_count,
+        average_word_length: letters as f32 / word_count,
+        syllables: syllable_count,
+        syllables_per_word: syllable_count as f32 / word_count,
+        wpm,
+        reading_minutes: words.len() as f32 / wpm,
+        readability: (poem_mode && !words.is_empty())
+            .then(|| readability(words.len(), sentence_count, &syllable_counts, long_words)),
+    }
+}
+
+// reads and splits the file like the reader does
+pub fn load(file: &Path, mode: TextMode, language: Language, wpm: f32) -> Option<Analysis> {
+    let (entries, poem_mode) =
+        read_words_from_file(file).map(|(w, poem_mode)| apply_mode(w, poem_mode, mode))?;
+    Some(analyze(file, &entries, poem_mode, language, wpm))
+}
+
+impl Analysis {
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "file:                  {}\n\
+             mode:                  {}\n\
+             language:              {:?}\n\
+             words:                 {}\n\
+             unique words:          {}\n\
+             sentences:             {}\n\
+             average word length:   {:.1} letters\n\
+             syllables:             {} ({:.2} per word)\n\
+             reading time:          {:.1} min at {:.0} wpm\n",
+            self.file,
+            self.mode,
+            self.language,
+            self.words,
+            self.unique_words,
+            self.sentences,
+            self.average_word_length,
+            self.syllables,
+            self.syllables_per_word,
+            self.reading_minutes,
+            self.wpm,
+        );
+        match &self.readability {
+            Some(readability) => {
+                let (index, score) = readability.main_score(self.language);
+                text += &format!(
+                    "readability:           {:.0} ({})\n\
+                     LIX:                   {:.0}\n\
+                     Wiener Sachtextformel: {:.1}\n",
+                    score, index, readability.lix, readability.wiener
+                );
+            }
+            None => text += "readability:           not applicable for word lists\n",
+        }
+        text
+    }
+}
+
+// returns true when the language was changed and the text has to be analyzed again
+pub fn file_info_window(
+    ctx: &egui::Context,
+    open: &mut bool,
+    analysis: Option<&Analysis>,
+    language: &mut Language,
+    rate: f32,
+) -> bool {
+    let before = *language;
+    egui::Window::new("Dateiinfo")
+        .open(open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let Some(analysis) = analysis else {
+                ui.label("Die Datei konnte nicht gelesen werden.");
+                return;
+            };
+            egui::Grid::new("file_info").show(ui, |ui| {
+                ui.label("Datei:");
+                ui.label(
+                    Path::new(&analysis.file)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                )
+                .on_hover_text(&analysis.file);
+                ui.end_row();
+
+                ui.label("Sprache:");
+                egui::ComboBox::from_id_salt("language")
+                    .selected_text(if *language == Language::Auto {
+                        format!("{} ({})", language.label(), analysis.language.label())
+                    } else {
+                        language.label().to_string()
+                    })
+                    .show_ui(ui, |ui| {
+                        for l in [Language::Auto, Language::German, Language::English] {
+                            ui.selectable_value(language, l, l.label());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Wörter:");
+                ui.label(format!(
+                    "{} ({} verschiedene)",
+                    analysis.words, analysis.unique_words
+                ));
+                ui.end_row();
+
+                if analysis.mode == "poem" {
+                    ui.label("Sätze:");
+                    ui.label(analysis.sentences.to_string());
+                    ui.end_row();
+                }
+
+                ui.label("Wortlänge:");
+                ui.label(format!(
+                    "{:.1} Buchstaben, {:.2} Silben",
+                    analysis.average_word_length, analysis.syllables_per_word
+                ));
+                ui.end_row();
+
+                ui.label("Lesezeit:");
+                ui.label(format!(
+                    "{:.1} min bei {:.0} wpm",
+                    analysis.words as f32 / rate,
+                    rate
+                ));
+                ui.end_row();
+
+                if let Some(readability) = &analysis.readability {
+                    let (index, score) = readability.main_score(analysis.language);
+                    ui.label(format!("{}:", index));
+                    ui.label(format!("{:.0} – {}", score, flesch_label(score)))
+                        .on_hover_text("0 = sehr schwer, 100 = sehr leicht");
+                    ui.end_row();
+
+                    ui.label("LIX:");
+                    ui.label(format!(
+                        "{:.0} – {}",
+                        readability.lix,
+                        lix_label(readability.lix)
+                    ))
+                    .on_hover_text("Satzlänge plus Anteil der Wörter mit mehr als 6 Buchstaben");
+                    ui.end_row();
+
+                    if analysis.language == Language::German {
+                        ui.label("Wiener Sachtextformel:");
+                        ui.label(format!("Schulstufe {:.1}", readability.wiener))
+                            .on_hover_text("Für Sachtexte, von 4 (leicht) bis 15 (sehr schwer)");
+                        ui.end_row();
+                    }
+                }
+            });
+            if analysis.mode != "poem" {
+                ui.add_space(5.0);
+                ui.label("Wortliste: die Lesbarkeit gilt nur für Texte.");
+            }
+        });
+    *language != before
+}
+
+// loads the file like the GUI does and prints the analysis, returns false if it could not be read
+pub fn run(file: &Path, mode: TextMode, language: Language, wpm: f32, json: bool) -> bool {
+    // like opening the file in the app, the header chooses the mode unless one is given
+    let mode = match mode {
+        TextMode::Auto => read_front_matter(file).mode.unwrap_or_default(),
+        mode => mode,
+    };
+    let Some(analysis) = load(file, mode, language, wpm) else {
+        eprintln!("could not read {}", file.display());
+        return false;
+    };
+    // an empty file or one with only a header
+    if analysis.words == 0 {
+        eprintln!("no text in {}", file.display());
+        return false;
+    }
+    if json {
+        match serde_json::to_string_pretty(&analysis) {
+            Ok(json) => println!("{}", json),
+            Err(err) => {
+                eprintln!("analysis serialization failed: {:?}", err);
+                return false;
+            }
+        }
+    } else {
+        print!("{}", analysis.to_text());
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poem(text: &str, language: Language) -> Analysis {
+        analyze(
+            Path::new("test.txt"),
+            &[Entry::new(text)],
+            true,
+            language,
+            120.0,
+        )
+    }
+
+    fn scores(analysis: &Analysis) -> &Readability {
+        analysis
+            .readability
+            .as_ref()
+            .expect("a text has a readability")
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.01,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn counts_syllables() {
+        assert_eq!(syllables("Sonne", Language::German), 2);
+        assert_eq!(syllables("scheint", Language::German), 1);
+        assert_eq!(syllables("Schmetterling", Language::German), 3);
+        assert_eq!(syllables("Käse", Language::German), 2);
+        assert_eq!(syllables("make", Language::English), 1);
+        assert_eq!(syllables("table", Language::English), 2);
+        assert_eq!(syllables("lazy", Language::English), 2);
+    }
+
+    #[test]
+    fn detects_language() {
+        assert_eq!(
+            poem("Die Kinder spielen im Garten.", Language::Auto).language,
+            Language::German
+        );
+        assert_eq!(
+            poem("The children are playing in the garden.", Language::Auto).language,
+            Language::English
+        );
+        // a word list without common words stays German
+        assert_eq!(detect_language(&["Haus", "Baum"]), Language::German);
+    }
+
+    #[test]
+    fn flesch_reference_scores() {
+        // the examples of the Flesch reading ease article on Wikipedia:
+        // "The Australian platypus is seemingly a hybrid of a mammal and reptilian creature."
+        // has 13 words and 26 syllables and scores 24.4
+        let platypus = readability(13, 1, &[1, 4, 3, 1, 3, 1, 2, 1, 1, 2, 1, 4, 2], 5);
+        assert!((platypus.flesch - 24.4).abs() < 0.05, "{}", platypus.flesch);
+        // "The cat sat on the mat." scores 116, above the end of the scale
+        let analysis = poem("The cat sat on the mat.", Language::Auto);
+        assert_eq!(analysis.language, Language::English);
+        assert_eq!(analysis.syllables, 6);
+        assert_close(scores(&analysis).flesch, 100.0);
+        assert_eq!(scores(&analysis).main_score(analysis.language).0, "Flesch");
+    }
+
+    // there is no published German sample with its counts, so the formulas are checked by hand
+    #[test]
+    fn german_formulas() {
+        // 8 words, 2 sentences, 12 syllables, 2 words longer than 6 letters, 4 with one syllable
+        let analysis = poem(
+            "Die Sonne scheint. Die Kinder spielen im Garten.",
+            Language::Auto,
+        );
+        assert_eq!(analysis.words, 8);
+        assert_eq!(analysis.sentences, 2);
+        assert_eq!(analysis.syllables, 12);
+        // 180 - 4 - 58.5 * 1.5
+        assert_close(scores(&analysis).amstad, 88.25);
+        // 4 + 100 * 2 / 8
+        assert_close(scores(&analysis).lix, 29.0);
+        // 0.1935 * 0 + 0.1672 * 4 + 0.1297 * 25 - 0.0327 * 50 - 0.875
+        assert_close(scores(&analysis).wiener, 1.4013);
+        assert_eq!(
+            scores(&analysis).main_score(analysis.language),
+            ("Flesch-Amstad", 88.25)
+        );
+    }
+
+    #[test]
+    fn configured_language_wins() {
+        let analysis = poem("Die Sonne scheint.", Language::English);
+        assert_eq!(analysis.language, Language::English);
+        assert_eq!(scores(&analysis).main_score(analysis.language).0, "Flesch");
+    }
+
+    #[test]
+    fn scores_are_clamped() {
+        let analysis = poem("Ja. Nein. Gut.", Language::German);
+        assert_close(scores(&analysis).amstad, 100.0);
+        let analysis = poem(
+            "Donaudampfschifffahrtsgesellschaftskapitänsmützenabzeichenherstellungsverordnung",
+            Language::German,
+        );
+        assert_close(scores(&analysis).amstad, 0.0);
+    }
+
+    #[test]
+    fn word_lists_have_no_readability() {
+        let entries: Vec<Entry> = ["a", "b", "c", "Haus"]
+            .into_iter()
+            .map(Entry::new)
+            .collect();
+        let analysis = analyze(Path::new("abc.txt"), &entries, false, Language::Auto, 120.0);
+        assert_eq!(analysis.sentences, 4);
+        assert_eq!(analysis.readability, None);
+        assert!(analysis.to_text().contains("not applicable for word lists"));
+        // the letter a is not the English article
+        assert_eq!(analysis.language, Language::German);
+    }
+
+    #[test]
+    fn empty_file_is_no_text() {
+        let path = std::env::temp_dir().join(format!("word-nerd-{}-leer.txt", std::process::id()));
+        std::fs::write(&path, "---\nrate: 60\n---\n").unwrap();
+        let ok = run(&path, TextMode::Auto, Language::Auto, 120.0, false);
+        std::fs::remove_file(&path).unwrap();
+        assert!(!ok);
+    }
+}
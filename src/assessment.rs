@@ -0,0 +1,138 @@
+use chrono::{DateTime, Local};
+use eframe::egui;
+use eframe::egui::RichText;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct AssessmentResult {
+    pub file: String,
+    pub date: DateTime<Local>,
+    pub seconds: f32,
+    pub words_read: u32,
+    pub correct: u32,
+    pub wcpm: f32,
+    pub accuracy: f32,
+    pub errors: Vec<String>,
+}
+
+// timed read-aloud test, the teacher marks every displayed word
+pub struct Assessment {
+    started: Option<Instant>,
+    duration: Duration,
+    marks: Vec<(String, bool)>,
+}
+
+impl Assessment {
+    pub fn new() -> Assessment {
+        Assessment {
+            started: None,
+            duration: Duration::ZERO,
+            marks: vec![],
+        }
+    }
+
+    pub fn start(&mut self, seconds: f32) {
+        self.started = Some(Instant::now());
+        self.duration = Duration::from_secs_f32(seconds);
+        self.marks.clear();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.started
+            .is_some_and(|started| started.elapsed() >= self.duration)
+    }
+
+    pub fn remaining(&self) -> Duration {
+        match self.started {
+            Some(started) => self.duration.saturating_sub(started.elapsed()),
+            None => self.duration,
+        }
+    }
+
+    pub fn mark(&mut self, word: &str, correct: bool) {
+        if self.is_running() {
+            self.marks.push((word.to_string(), correct));
+        }
+    }
+
+    pub fn undo(&mut self) {
+        if self.is_running() {
+            self.marks.pop();
+        }
+    }
+
+    pub fn correct(&self) -> u32 {
+        self.marks.iter().filter(|(_, correct)| *correct).count() as u32
+    }
+
+    pub fn incorrect(&self) -> u32 {
+        self.marks.len() as u32 - self.correct()
+    }
+
+    pub fn finish(&mut self, file: &Path) -> Option<AssessmentResult> {
+        let started = self.started.take()?;
+        // stopping early only counts the time that was actually read
+        let seconds = started.elapsed().min(self.duration).as_secs_f32();
+        let words_read = self.marks.len() as u32;
+        let correct = self.correct();
+        Some(AssessmentResult {
+            file: file.display().to_string(),
+            date: Local::now(),
+            seconds,
+            words_read,
+            correct,
+            wcpm: if seconds > 0.0 {
+                correct as f32 / seconds * 60.0
+            } else {
+                0.0
+            },
+            accuracy: if words_read > 0 {
+                correct as f32 / words_read as f32 * 100.0
+            } else {
+                0.0
+            },
+            errors: self
+                .marks
+                .drain(..)
+                .filter(|(_, correct)| !correct)
+                .map(|(word, _)| word)
+                .collect(),
+        })
+    }
+}
+
+pub fn assessment_result_window(ctx: &egui::Context, result: &mut Option<AssessmentResult>) {
+    let mut open = true;
+    if let Some(r) = result.as_ref() {
+        egui::Window::new("Ergebnis Lesetest")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(format!("{:.0} Wörter korrekt pro Minute", r.wcpm))
+                        .size(24.0)
+                        .strong(),
+                );
+                ui.add_space(10.0);
+                ui.label(format!(
+                    "{} von {} Wörtern richtig ({:.0} %) in {:.0} s",
+                    r.correct, r.words_read, r.accuracy, r.seconds
+                ));
+                if !r.errors.is_empty() {
+                    ui.add_space(10.0);
+                    ui.label(RichText::new("Fehler:").strong());
+                    ui.label(r.errors.join(", "));
+                }
+            });
+    }
+    if !open {
+        *result = None;
+    }
+}
@@ -0,0 +1,364 @@
+use crate::io::read_words_from_file;
+use rodio::{Decoder, OutputStream, OutputStreamBuilder, Sink, Source};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+// a decoded recording, kept in memory so it can be played again without decoding
+#[derive(Clone)]
+pub struct Clip {
+    channels: u16,
+    sample_rate: u32,
+    samples: Vec<f32>,
+}
+
+impl Clip {
+    pub fn mono(sample_rate: u32, samples: Vec<f32>) -> Clip {
+        Clip {
+            channels: 1,
+            sample_rate,
+            samples,
+        }
+    }
+
+    pub fn decode(path: &Path) -> io::Result<Clip> {
+        let decoder = Decoder::try_from(File::open(path)?).map_err(io::Error::other)?;
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+        Ok(Clip {
+            channels,
+            sample_rate,
+            samples: decoder.collect(),
+        })
+    }
+}
+
+pub trait AudioOutput {
+    fn play(&mut self, clip: &Clip);
+    // plays on top of whatever is playing, for short cues
+    fn mix(&mut self, clip: &Clip, volume: f32);
+    fn stop(&mut self);
+}
+
+// plays on the default sound device
+pub struct RodioOutput {
+    // the stream has to stay alive as long as something is played
+    stream: OutputStream,
+    sink: Sink,
+}
+
+impl RodioOutput {
+    pub fn open() -> Option<RodioOutput> {
+        match OutputStreamBuilder::open_default_stream() {
+            Ok(mut stream) => {
+                stream.log_on_drop(false);
+                let sink = Sink::connect_new(stream.mixer());
+                Some(RodioOutput { stream, sink })
+            }
+            Err(err) => {
+                println!("no audio output: {:?}", err);
+                None
+            }
+        }
+    }
+}
+
+impl AudioOutput for RodioOutput {
+    fn play(&mut self, clip: &Clip) {
+        // a new word cuts off the previous recording
+        self.sink.clear();
+        self.sink.append(rodio::buffer::SamplesBuffer::new(
+            clip.channels,
+            clip.sample_rate,
+            clip.samples.clone(),
+        ));
+        self.sink.play();
+    }
+
+    fn mix(&mut self, clip: &Clip, volume: f32) {
+        let source = rodio::buffer::SamplesBuffer::new(
+            clip.channels,
+            clip.sample_rate,
+            clip.samples.clone(),
+        );
+        self.stream.mixer().add(source.amplify(volume));
+    }
+
+    fn stop(&mut self) {
+        self.sink.clear();
+    }
+}
+
+// discards everything, used without a sound device and in tests
+pub struct NullOutput;
+
+impl AudioOutput for NullOutput {
+    fn play(&mut self, _clip: &Clip) {}
+
+    fn mix(&mut self, _clip: &Clip, _volume: f32) {}
+
+    fn stop(&mut self) {}
+}
+
+type Decoded = (PathBuf, Option<Clip>);
+
+// long recordings take a while to decode, so it happens in the background and never in a frame
+fn decode_all(paths: Vec<PathBuf>, decoded_tx: Sender<Decoded>) {
+    std::thread::spawn(move || {
+        for path in paths {
+            // broken files are remembered as well, so they are only reported once
+            let clip = Clip::decode(&path)
+                .map_err(|err| println!("could not decode {}: {}", path.display(), err))
+                .ok();
+            // the list was changed in the meantime
+            if decoded_tx.send((path, clip)).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+// recordings decoded ahead of the reader, a long list is not held in memory as a whole
+const DECODE_AHEAD: usize = 8;
+
+pub struct AudioClips {
+    output: Box<dyn AudioOutput>,
+    cache: HashMap<PathBuf, Option<Clip>>,
+    pending: HashSet<PathBuf>,
+    // the recording of every word of the list in reading order, read in the background
+    recordings: Vec<Option<PathBuf>>,
+    recordings_rx: Option<Receiver<Vec<Option<PathBuf>>>>,
+    position: usize,
+    decoded_tx: Sender<Decoded>,
+    decoded_rx: Receiver<Decoded>,
+}
+
+impl AudioClips {
+    pub fn new(output: Box<dyn AudioOutput>) -> AudioClips {
+        let (decoded_tx, decoded_rx) = channel();
+        AudioClips {
+            output,
+            cache: HashMap::new(),
+            pending: HashSet::new(),
+            recordings: vec![],
+            recordings_rx: None,
+            position: 0,
+            decoded_tx,
+            decoded_rx,
+        }
+    }
+
+    pub fn with_default_output() -> AudioClips {
+        match RodioOutput::open() {
+            Some(output) => AudioClips::new(Box::new(output)),
+            None => AudioClips::new(Box::new(NullOutput)),
+        }
+    }
+
+    // takes the decoded clips, and the recordings of a newly loaded list
+    pub fn update(&mut self) {
+        if let Some(Ok(recordings)) = self.recordings_rx.as_ref().map(|rx| rx.try_recv()) {
+            self.recordings = recordings;
+            self.recordings_rx = None;
+            self.decode_ahead(self.position);
+        }
+        while let Ok((path, clip)) = self.decoded_rx.try_recv() {
+            self.pending.remove(&path);
+            self.cache.insert(path, clip);
+        }
+    }
+
+    fn decode(&mut self, paths: Vec<PathBuf>) {
+        // registered before the thread starts, so a word coming up meanwhile is not decoded twice
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .filter(|p| !self.cache.contains_key(p) && self.pending.insert(p.clone()))
+            .collect();
+        if !paths.is_empty() {
+            decode_all(paths, self.decoded_tx.clone());
+        }
+    }
+
+    // the recordings of the word at the position and the next words are decoded, the others dropped;
+    // broken files stay remembered
+    pub fn decode_ahead(&mut self, position: usize) {
+        self.position = position;
+        let ahead: Vec<PathBuf> = self
+            .recordings
+            .iter()
+            .cycle()
+            .skip(position)
+            .take(self.recordings.len())
+            .flatten()
+            .take(DECODE_AHEAD)
+            .cloned()
+            .collect();
+        self.cache
+            .retain(|path, clip| clip.is_none() || ahead.contains(path));
+        self.decode(ahead);
+    }
+
+    // returns false if the recording could not be played or is not decoded yet
+    pub fn play(&mut self, path: &Path) -> bool {
+        self.update();
+        match self.cache.get(path) {
+            Some(Some(clip)) => {
+                self.output.play(clip);
+                true
+            }
+            Some(None) => false,
+            None => {
+                // ready the next time the word comes up, e.g. in random order
+                self.decode(vec![path.to_path_buf()]);
+                false
+            }
+        }
+    }
+
+    pub fn cue(&mut self, clip: &Clip, volume: f32) {
+        self.output.mix(clip, volume);
+    }
+
+    pub fn stop(&mut self) {
+        self.output.stop();
+    }
+
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+        self.pending.clear();
+        self.recordings.clear();
+        self.recordings_rx = None;
+        // clips of the old list still being decoded are dropped
+        (self.decoded_tx, self.decoded_rx) = channel();
+    }
+
+    // recordings may have changed together with the list, the ones from the position on are decoded again
+    pub fn load(&mut self, file: &Path, position: usize) {
+        self.clear_cache();
+        self.position = position;
+        let (recordings_tx, recordings_rx) = channel();
+        self.recordings_rx = Some(recordings_rx);
+        let file = file.to_path_buf();
+        std::thread::spawn(move || {
+            let recordings = read_words_from_file(&file)
+                .map(|(words, _)| words)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|w| w.audio)
+                .collect();
+            let _ = recordings_tx.send(recordings);
+        });
+    }
+
+    #[cfg(test)]
+    fn wait_decoded(&mut self) {
+        while !self.pending.is_empty() {
+            if let Ok((path, clip)) = self.decoded_rx.recv() {
+                self.pending.remove(&path);
+                self.cache.insert(path, clip);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    // a short mono 16 bit wav file
+    fn write_wav(path: &Path, samples: &[i16]) {
+        let data_len = (samples.len() * 2) as u32;
+        let mut bytes = vec![];
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&8000u32.to_le_bytes());
+        bytes.extend_from_slice(&16000u32.to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for s in samples {
+            bytes.extend_from_slice(&s.to_le_bytes());
+        }
+        File::create(path).unwrap().write_all(&bytes).unwrap();
+    }
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("word-nerd-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn decodes_wav() {
+        let path = temp_file("hallo.wav");
+        write_wav(&path, &[0, 16384, -16384, 0]);
+        let clip = Clip::decode(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(clip.channels, 1);
+        assert_eq!(clip.sample_rate, 8000);
+        assert_eq!(clip.samples.len(), 4);
+        assert!((clip.samples[1] - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn plays_cached_clip_on_null_output() {
+        let path = temp_file("haus.wav");
+        write_wav(&path, &[0; 80]);
+        let mut clips = AudioClips::new(Box::new(NullOutput));
+        // decoded in the background, the first time the word is silent
+        assert!(!clips.play(&path));
+        clips.wait_decoded();
+        std::fs::remove_file(&path).unwrap();
+        // decoded once, the file is not needed anymore
+        assert!(clips.play(&path));
+    }
+
+    #[test]
+    fn decodes_only_the_next_recordings() {
+        let paths: Vec<PathBuf> = (0..20)
+            .map(|i| temp_file(&format!("wort{}.wav", i)))
+            .collect();
+        for path in &paths {
+            write_wav(path, &[0; 8]);
+        }
+        let mut clips = AudioClips::new(Box::new(NullOutput));
+        // every other word has a recording
+        clips.recordings = paths.iter().flat_map(|p| [Some(p.clone()), None]).collect();
+        clips.decode_ahead(0);
+        clips.wait_decoded();
+        assert_eq!(clips.cache.len(), DECODE_AHEAD);
+        assert!(clips.cache.contains_key(&paths[0]));
+        // ten words later the first five recordings are gone
+        clips.decode_ahead(10);
+        clips.wait_decoded();
+        assert_eq!(clips.cache.len(), DECODE_AHEAD);
+        assert!(!clips.cache.contains_key(&paths[4]));
+        assert!(clips.cache.contains_key(&paths[5]));
+        assert!(clips.cache.contains_key(&paths[12]));
+        // at the end of the list the first recordings come next
+        clips.decode_ahead(36);
+        clips.wait_decoded();
+        assert!(clips.cache.contains_key(&paths[0]));
+        for path in &paths {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn broken_clip_is_not_played() {
+        let path = temp_file("kaputt.ogg");
+        File::create(&path).unwrap().write_all(b"no audio").unwrap();
+        let mut clips = AudioClips::new(Box::new(NullOutput));
+        assert!(!clips.play(&path));
+        clips.wait_decoded();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!clips.play(&path));
+        assert!(!clips.play(Path::new("/does/not/exist.wav")));
+    }
+}
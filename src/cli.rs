@@ -0,0 +1,216 @@
+use crate::analysis::Language;
+use crate::gui::SettingsContainer;
+use crate::io::{read_front_matter, TextMode};
+use crate::lesson::is_lesson;
+use crate::profiles::{is_valid_name, Profiles};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(ValueEnum, PartialEq, Debug, Clone, Copy)]
+pub enum ModeArg {
+    Auto,
+    Words,
+    Poem,
+}
+
+impl ModeArg {
+    pub fn text_mode(&self) -> TextMode {
+        match self {
+            ModeArg::Auto => TextMode::Auto,
+            ModeArg::Words => TextMode::Words,
+            ModeArg::Poem => TextMode::Poem,
+        }
+    }
+}
+
+#[derive(ValueEnum, PartialEq, Debug, Clone, Copy)]
+pub enum LanguageArg {
+    Auto,
+    German,
+    English,
+}
+
+impl LanguageArg {
+    pub fn language(&self) -> Language {
+        match self {
+            LanguageArg::Auto => Language::Auto,
+            LanguageArg::German => Language::German,
+            LanguageArg::English => Language::English,
+        }
+    }
+}
+
+fn rate(s: &str) -> Result<f32, String> {
+    in_range(s, 10.0, 800.0)
+}
+
+fn font_size(s: &str) -> Result<f32, String> {
+    in_range(s, 40.0, 200.0)
+}
+
+fn in_range(s: &str, min: f32, max: f32) -> Result<f32, String> {
+    let value: f32 = s.parse().map_err(|_| format!("`{}` is not a number", s))?;
+    if (min..=max).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!("must be between {} and {}", min, max))
+    }
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    #[command(about = "Print statistics about a file without opening a window")]
+    Analyze {
+        #[arg(help = "Word list or text to analyze")]
+        file: PathBuf,
+        #[arg(long, value_parser = rate, default_value_t = 120.0, help = "Reading speed for the estimated reading time")]
+        wpm: f32,
+        #[arg(
+            long,
+            value_enum,
+            default_value = "auto",
+            help = "How the file is split"
+        )]
+        mode: ModeArg,
+        #[arg(
+            long,
+            value_enum,
+            default_value = "auto",
+            help = "Language for syllables and the readability index"
+        )]
+        language: LanguageArg,
+        #[arg(long, help = "Print JSON instead of text")]
+        json: bool,
+    },
+}
+
+// the options only apply to this session, the stored settings stay as they are
+#[derive(Parser, Debug, Clone, Default)]
+#[command(
+    version,
+    about = "A speed reader written in rust.",
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[arg(help = "Word list or text to open")]
+    pub file: Option<PathBuf>,
+    #[arg(long, value_parser = rate, help = "Words per minute (characters per minute for poems)")]
+    pub rate: Option<f32>,
+    #[arg(long, value_enum, help = "How the file is split")]
+    pub mode: Option<ModeArg>,
+    #[arg(long, help = "Show the words in random order")]
+    pub random: bool,
+    #[arg(long, value_parser = font_size, help = "Font size of the words")]
+    pub font_size: Option<f32>,
+    #[arg(long, help = "Start in fullscreen")]
+    pub fullscreen: bool,
+    #[arg(long, help = "Learner profile, created if it does not exist")]
+    pub profile: Option<String>,
+    #[arg(long, help = "Read in the terminal instead of a window, e.g. over SSH")]
+    pub tui: bool,
+}
+
+impl Cli {
+    pub fn select_profile(&self, profiles: &mut Profiles) -> Result<(), String> {
+        // trimmed like in the profile window, "Anna " is Anna
+        if let Some(name) = self.profile.as_deref().map(str::trim) {
+            if !is_valid_name(name) {
+                return Err(format!("invalid profile name `{}`", name));
+            }
+            profiles.add(name);
+            profiles.active = name.to_string();
+        }
+        Ok(())
+    }
+}
+
+// remembers what the command line changed so it is not written back
+pub struct Overrides {
+    cli: Cli,
+    stored: SettingsContainer,
+    stored_profile: String,
+}
+
+impl Overrides {
+    pub fn none() -> Overrides {
+        Overrides {
+            cli: Cli::default(),
+            stored: SettingsContainer::default(),
+            stored_profile: String::new(),
+        }
+    }
+
+    pub fn apply(cli: Cli, conf: &mut SettingsContainer, stored_profile: &str) -> Overrides {
+        let stored = conf.clone();
+        // a lesson is started by the window like an opened lesson, it brings its own files
+        if let Some(file) = cli.file.as_ref().filter(|f| !is_lesson(f)) {
+            conf.file_path = std::path::absolute(file).unwrap_or(file.clone());
+            conf.idx = 0;
+            // the options below win over the header of the file
+            conf.apply_front_matter(&read_front_matter(file));
+        }
+        if let Some(rate) = cli.rate {
+            conf.rate = rate;
+        }
+        if let Some(mode) = cli.mode {
+            conf.mode = mode.text_mode();
+        }
+        if cli.random {
+            conf.random = true;
+        }
+        if let Some(font_size) = cli.font_size {
+            conf.font_size = font_size;
+        }
+        Overrides {
+            cli,
+            stored,
+            stored_profile: stored_profile.to_string(),
+        }
+    }
+
+    pub fn lesson(&self) -> Option<PathBuf> {
+        self.cli
+            .file
+            .as_ref()
+            .filter(|f| is_lesson(f))
+            .map(|f| std::path::absolute(f).unwrap_or(f.clone()))
+    }
+
+    pub fn profile_given(&self) -> bool {
+        self.cli.profile.is_some()
+    }
+
+    // the settings to store: overridden values go back to the stored ones unless they were changed in the app
+    pub fn persisted(&self, conf: &SettingsContainer) -> SettingsContainer {
+        let mut persisted = conf.clone();
+        if let Some(file) = &self.cli.file {
+            if std::path::absolute(file).unwrap_or(file.clone()) == conf.file_path {
+                persisted.file_path = self.stored.file_path.clone();
+                persisted.idx = self.stored.idx;
+            }
+        }
+        if self.cli.rate == Some(conf.rate) {
+            persisted.rate = self.stored.rate;
+        }
+        if self.cli.mode.map(|m| m.text_mode()) == Some(conf.mode) {
+            persisted.mode = self.stored.mode;
+        }
+        if self.cli.random && conf.random {
+            persisted.random = self.stored.random;
+        }
+        if self.cli.font_size == Some(conf.font_size) {
+            persisted.font_size = self.stored.font_size;
+        }
+        persisted
+    }
+
+    pub fn persisted_profiles(&self, profiles: &Profiles) -> Profiles {
+        let mut persisted = profiles.clone();
+        if self.cli.profile.as_deref().map(str::trim) == Some(profiles.active.as_str()) {
+            persisted.active = self.stored_profile.clone();
+        }
+        persisted
+    }
+}
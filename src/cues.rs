@@ -0,0 +1,79 @@
+use crate::audio::Clip;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+const SAMPLE_RATE: u32 = 44100;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct CueSettings {
+    pub muted: bool,
+    pub volume: f32,
+    pub tick: bool,
+    pub sentence_end: bool,
+    pub wrap: bool,
+}
+
+impl CueSettings {
+    pub fn default() -> CueSettings {
+        CueSettings {
+            muted: true,
+            volume: 0.5,
+            tick: true,
+            sentence_end: true,
+            wrap: true,
+        }
+    }
+}
+
+// a decaying sine per note, the notes start one after another
+fn tone(notes: &[f32], note_ms: f32, decay_ms: f32, amplitude: f32) -> Clip {
+    let note_len = (SAMPLE_RATE as f32 * note_ms / 1000.0) as usize;
+    let len =
+        note_len * (notes.len() - 1) + (SAMPLE_RATE as f32 * decay_ms * 5.0 / 1000.0) as usize;
+    let mut samples = vec![0.0; len];
+    for (n, frequency) in notes.iter().enumerate() {
+        for (i, sample) in samples[n * note_len..].iter_mut().enumerate() {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let envelope = (-t * 1000.0 / decay_ms).exp();
+            *sample += amplitude * envelope * (TAU * frequency * t).sin();
+        }
+    }
+    Clip::mono(SAMPLE_RATE, samples)
+}
+
+fn is_sentence_end(word: &str) -> bool {
+    word.trim_end_matches(['"', '\'', '»', '«', '“', '”', ')', ']'])
+        .ends_with(['.', '!', '?', '…'])
+}
+
+// sounds are synthesized once, no audio files needed
+pub struct Cues {
+    tick: Clip,
+    sentence_end: Clip,
+    chime: Clip,
+}
+
+impl Cues {
+    pub fn new() -> Cues {
+        Cues {
+            tick: tone(&[1200.0], 0.0, 5.0, 0.2),
+            sentence_end: tone(&[660.0, 880.0], 60.0, 25.0, 0.3),
+            chime: tone(&[1046.5, 1318.5, 1568.0, 2093.0], 120.0, 120.0, 0.2),
+        }
+    }
+
+    // the cue for a word that was just shown; the chime wins over the sentence end over the tick
+    pub fn for_word(&self, settings: &CueSettings, word: &str, wrapped: bool) -> Option<&Clip> {
+        if settings.muted {
+            None
+        } else if wrapped && settings.wrap {
+            Some(&self.chime)
+        } else if settings.sentence_end && is_sentence_end(word) {
+            Some(&self.sentence_end)
+        } else if settings.tick && !word.trim().is_empty() {
+            Some(&self.tick)
+        } else {
+            None
+        }
+    }
+}
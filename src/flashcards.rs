@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct CardStats {
+    pub known: u32,
+    pub unknown: u32,
+}
+
+// shows the front of a card for a while, then reveals the back until it is marked
+pub struct Flashcards {
+    running: bool,
+    shown_at: Option<Instant>,
+    known: u32,
+    unknown: u32,
+}
+
+impl Flashcards {
+    pub fn new() -> Flashcards {
+        Flashcards {
+            running: false,
+            shown_at: None,
+            known: 0,
+            unknown: 0,
+        }
+    }
+
+    pub fn start(&mut self) {
+        *self = Flashcards::new();
+        self.running = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.running = false;
+        self.shown_at = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn card_shown(&mut self) {
+        if self.running {
+            self.shown_at = Some(Instant::now());
+        }
+    }
+
+    // time left until the back is revealed, None once it is visible
+    pub fn time_to_reveal(&self, front_ms: u32) -> Option<Duration> {
+        let shown_at = self.shown_at?;
+        Duration::from_millis(front_ms as u64)
+            .checked_sub(shown_at.elapsed())
+            .filter(|d| !d.is_zero())
+    }
+
+    pub fn is_revealed(&self, front_ms: u32) -> bool {
+        self.shown_at.is_some() && self.time_to_reveal(front_ms).is_none()
+    }
+
+    pub fn mark(&mut self, known: bool) -> bool {
+        if self.shown_at.take().is_none() {
+            return false;
+        }
+        if known {
+            self.known += 1;
+        } else {
+            self.unknown += 1;
+        }
+        true
+    }
+
+    pub fn known(&self) -> u32 {
+        self.known
+    }
+
+    pub fn unknown(&self) -> u32 {
+        self.unknown
+    }
+}
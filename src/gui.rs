@@ -1,10 +1,29 @@
-use crate::APP_INFO;
+use crate::analysis::{file_info_window, Analysis, Language};
+use crate::assessment::{assessment_result_window, Assessment, AssessmentResult};
+use crate::audio::AudioClips;
+use crate::cli::Overrides;
+use crate::cues::{CueSettings, Cues};
+use crate::flashcards::Flashcards;
+use crate::io::{read_front_matter, write_words_to_file, Entry, FrontMatter, TextMode};
+use crate::lesson::{is_lesson, Lesson, LessonRun, LessonState};
+use crate::library::{add_recent, library_panel, Library};
+use crate::peripheral::{Pattern, Peripheral, PeripheralSettings};
+use crate::pictures::{PictureCards, PictureSettings, Placement};
+use crate::profiles::{is_valid_name, load_settings, save_settings, Profiles};
+use crate::ramp::{Ramp, RampSettings, RampUnit};
+use crate::reaction::{adapted_rate, Reaction};
+use crate::schulte::{schulte_window, SchulteSettings, SchulteTable};
+use crate::speech::{Dictation, EspeakBackend, SpeechSettings, SpeechTiming};
+use crate::spelling::{diff_layout, Spelling};
+use crate::stats::{statistics_window, History, SessionTracker};
+use crate::tachistoscope::{MaskStyle, Tachistoscope, TachistoscopeSettings};
+use crate::watch::FileWatch;
 use eframe::egui::{Align2, DragValue, FontFamily, FontId, Pos2, RichText, Sense, Vec2};
 use eframe::{egui, Storage};
 use egui_theme_switch::global_theme_switch;
-use preferences::Preferences;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, RwLock};
 use std::time::Instant;
@@ -14,6 +33,82 @@ pub enum StepDir {
     BACKWARD,
 }
 
+const START_HINT: &str = "Drücke auf Start...";
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub enum Exercise {
+    #[default]
+    Reading,
+    Assessment,
+    Reaction,
+    Ramp,
+    Tachistoscope,
+    Peripheral,
+    Flashcards,
+    Pictures,
+    Spelling,
+}
+
+impl Exercise {
+    pub const ALL: [Exercise; 9] = [
+        Exercise::Reading,
+        Exercise::Assessment,
+        Exercise::Reaction,
+        Exercise::Ramp,
+        Exercise::Tachistoscope,
+        Exercise::Peripheral,
+        Exercise::Flashcards,
+        Exercise::Pictures,
+        Exercise::Spelling,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Exercise::Reading => "Lesen",
+            Exercise::Assessment => "Lesetest",
+            Exercise::Reaction => "Reaktionszeit",
+            Exercise::Ramp => "Tempotraining",
+            Exercise::Tachistoscope => "Tachistoskop",
+            Exercise::Peripheral => "Blickspanne",
+            Exercise::Flashcards => "Karteikarten",
+            Exercise::Pictures => "Bildkarten",
+            Exercise::Spelling => "Rechtschreibung",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Progress {
+    pub position: usize,
+    pub words_shown: u64,
+    // how often the file was read to the end and started over
+    pub wraps: u64,
+}
+
+pub struct LoadRequest {
+    pub path: PathBuf,
+    pub position: usize,
+    pub mode: TextMode,
+    // the word on screen, found again near the position when the file changed
+    pub word: Option<String>,
+}
+
+impl LoadRequest {
+    pub fn from_settings(conf: &SettingsContainer) -> LoadRequest {
+        LoadRequest {
+            path: conf.file_path.clone(),
+            position: conf.idx.max(0) as usize,
+            // exercises always show one word at a time
+            mode: if matches!(conf.exercise, Exercise::Reading | Exercise::Ramp) {
+                conf.mode
+            } else {
+                TextMode::Words
+            },
+            word: None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct SettingsContainer {
     pub rate: f32,
@@ -23,6 +118,68 @@ pub struct SettingsContainer {
     pub font_size: f32,
     pub x: f32,
     pub y: f32,
+    // words flagged as difficult, grouped by the file they were read in
+    #[serde(default)]
+    pub difficult_words: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub mode: TextMode,
+    #[serde(default)]
+    pub exercise: Exercise,
+    #[serde(default = "default_assessment_seconds")]
+    pub assessment_seconds: f32,
+    #[serde(default = "default_true")]
+    pub adapt_rate: bool,
+    #[serde(default = "default_reaction_window")]
+    pub reaction_window: usize,
+    #[serde(default = "RampSettings::default")]
+    pub ramp: RampSettings,
+    #[serde(default = "TachistoscopeSettings::default")]
+    pub tachistoscope: TachistoscopeSettings,
+    #[serde(default = "PeripheralSettings::default")]
+    pub peripheral: PeripheralSettings,
+    #[serde(default = "SchulteSettings::default")]
+    pub schulte: SchulteSettings,
+    #[serde(default = "default_flashcard_front_ms")]
+    pub flashcard_front_ms: u32,
+    #[serde(default = "PictureSettings::default")]
+    pub pictures: PictureSettings,
+    #[serde(default = "default_spelling_flash_ms")]
+    pub spelling_flash_ms: u32,
+    #[serde(default = "SpeechSettings::default")]
+    pub speech: SpeechSettings,
+    #[serde(default = "default_true")]
+    pub audio_clips: bool,
+    #[serde(default = "CueSettings::default")]
+    pub cues: CueSettings,
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub recent_files: Vec<PathBuf>,
+    #[serde(default)]
+    pub library_folder: Option<PathBuf>,
+    // the last position in every file that was read, for the library and the recent files
+    #[serde(default)]
+    pub positions: BTreeMap<String, usize>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_assessment_seconds() -> f32 {
+    60.0
+}
+
+fn default_reaction_window() -> usize {
+    10
+}
+
+fn default_flashcard_front_ms() -> u32 {
+    3000
+}
+
+fn default_spelling_flash_ms() -> u32 {
+    1000
 }
 
 impl SettingsContainer {
@@ -35,25 +192,149 @@ impl SettingsContainer {
             font_size: 50.0,
             x: 450.0,
             y: 900.0,
+            difficult_words: BTreeMap::new(),
+            mode: TextMode::Auto,
+            exercise: Exercise::Reading,
+            assessment_seconds: default_assessment_seconds(),
+            adapt_rate: true,
+            reaction_window: default_reaction_window(),
+            ramp: RampSettings::default(),
+            tachistoscope: TachistoscopeSettings::default(),
+            peripheral: PeripheralSettings::default(),
+            schulte: SchulteSettings::default(),
+            flashcard_front_ms: default_flashcard_front_ms(),
+            pictures: PictureSettings::default(),
+            spelling_flash_ms: default_spelling_flash_ms(),
+            speech: SpeechSettings::default(),
+            audio_clips: true,
+            cues: CueSettings::default(),
+            language: Language::Auto,
+            recent_files: vec![],
+            library_folder: None,
+            positions: BTreeMap::new(),
         };
     }
+
+    fn file_key(&self) -> String {
+        self.file_path.display().to_string()
+    }
+
+    // a file can bring its own settings, they are applied when it is opened
+    pub fn apply_front_matter(&mut self, header: &FrontMatter) {
+        if let Some(rate) = header.rate {
+            self.rate = rate;
+        }
+        if let Some(mode) = header.mode {
+            self.mode = mode;
+        }
+        if let Some(random) = header.random {
+            self.random = random;
+        }
+        if let Some(font_size) = header.font_size {
+            self.font_size = font_size;
+        }
+    }
+
+    fn stored_position(&self, path: &Path) -> isize {
+        self.positions
+            .get(&path.display().to_string())
+            .map_or(0, |p| *p as isize)
+    }
+
+    pub fn difficult_words(&self) -> &[String] {
+        self.difficult_words
+            .get(&self.file_key())
+            .map(|w| w.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn toggle_difficult_word(&mut self, word: &str) {
+        // a pause has no word
+        if word.trim().is_empty() {
+            return;
+        }
+        let key = self.file_key();
+        let words = self.difficult_words.entry(key.clone()).or_default();
+        if let Some(pos) = words.iter().position(|w| w == word) {
+            words.remove(pos);
+        } else {
+            words.push(word.to_string());
+        }
+        if words.is_empty() {
+            self.difficult_words.remove(&key);
+        }
+    }
+}
+
+// moves a scrolling text to the left and wraps it around, returns how often it went past completely
+pub fn scroll_text(offset: &mut f32, distance: f32, cycle: f32) -> u32 {
+    if cycle <= 0.0 {
+        *offset = 0.0;
+        return 0;
+    }
+    *offset -= distance;
+    let passes = (-*offset / cycle).floor().max(0.0) as u32;
+    *offset %= cycle;
+    passes
 }
 
 pub struct MyApp {
     running: bool,
-    word: String,
+    word: Entry,
     conf: SettingsContainer,
     random_lock: Arc<RwLock<bool>>,
     rate_lock: Arc<RwLock<f32>>,
     running_lock: Arc<RwLock<bool>>,
-    word_lock: Arc<RwLock<String>>,
+    word_lock: Arc<RwLock<Entry>>,
     mode_lock: Arc<RwLock<bool>>,
+    progress_lock: Arc<RwLock<Progress>>,
     step_tx: Sender<StepDir>,
-    load_tx: Sender<PathBuf>,
+    load_tx: Sender<LoadRequest>,
 
     // scrolling animation state (for poem/paragraph mode)
     scroll_offset: f32,
+    // how often the scrolling text went past completely since the engine was polled
+    text_passes: u32,
     last_instant: Instant,
+
+    show_difficult_words: bool,
+
+    profiles: Profiles,
+    overrides: Overrides,
+    show_profiles: bool,
+    new_profile_name: String,
+
+    history: History,
+    session: SessionTracker,
+    words_shown: u64,
+    show_stats: bool,
+    show_file_info: bool,
+    file_info: Option<Analysis>,
+    // dropped files are read one after another, the next one when the current one starts over
+    playlist: Vec<PathBuf>,
+    playlist_pos: usize,
+    show_library: bool,
+    library: Option<Library>,
+    lesson: Option<LessonRun>,
+    watch: FileWatch,
+    reloaded_at: Option<Instant>,
+
+    show_settings: bool,
+    assessment: Assessment,
+    assessment_result: Option<AssessmentResult>,
+    reaction: Reaction,
+    ramp: Ramp,
+    wraps: u64,
+    tachistoscope: Tachistoscope,
+    peripheral: Peripheral,
+    show_schulte: bool,
+    schulte: SchulteTable,
+    flashcards: Flashcards,
+    pictures: PictureCards,
+    spelling: Spelling,
+    speech: Dictation,
+    clips: AudioClips,
+    cues: Cues,
 }
 
 impl MyApp {
@@ -61,36 +342,973 @@ impl MyApp {
         random_lock: Arc<RwLock<bool>>,
         rate_lock: Arc<RwLock<f32>>,
         running_lock: Arc<RwLock<bool>>,
-        word_lock: Arc<RwLock<String>>,
+        word_lock: Arc<RwLock<Entry>>,
         mode_lock: Arc<RwLock<bool>>,
+        progress_lock: Arc<RwLock<Progress>>,
         conf: SettingsContainer,
+        profiles: Profiles,
+        overrides: Overrides,
         step_tx: Sender<StepDir>,
-        load_tx: Sender<PathBuf>,
+        load_tx: Sender<LoadRequest>,
     ) -> Self {
-        Self {
+        let mut app = Self {
             running: false,
-            word: "Hallo".to_string(),
-            conf,
+            word: Entry::new("Hallo"),
             random_lock,
             rate_lock,
             running_lock,
             word_lock,
             mode_lock,
+            progress_lock,
             step_tx,
             load_tx,
             scroll_offset: 0.0,
+            text_passes: 0,
             last_instant: Instant::now(),
+            show_difficult_words: false,
+            // several learners share a machine, so ask who is reading on startup
+            show_profiles: profiles.names.len() > 1 && !overrides.profile_given(),
+            history: History::load_for(&profiles.active),
+            profiles,
+            overrides,
+            new_profile_name: String::new(),
+            session: SessionTracker::new(),
+            words_shown: 0,
+            show_stats: false,
+            show_file_info: false,
+            playlist: vec![],
+            playlist_pos: 0,
+            show_library: false,
+            library: None,
+            lesson: None,
+            watch: FileWatch::new(&conf.file_path),
+            reloaded_at: None,
+            file_info: crate::analysis::load(&conf.file_path, conf.mode, conf.language, conf.rate),
+            show_settings: false,
+            assessment: Assessment::new(),
+            assessment_result: None,
+            reaction: Reaction::new(),
+            ramp: Ramp::new(&conf.ramp),
+            wraps: 0,
+            tachistoscope: Tachistoscope::new(),
+            peripheral: Peripheral::new(conf.peripheral.seed),
+            show_schulte: false,
+            flashcards: Flashcards::new(),
+            pictures: PictureCards::new(),
+            spelling: Spelling::new(),
+            speech: Dictation::new(Box::new(EspeakBackend::new())),
+            clips: AudioClips::with_default_output(),
+            cues: Cues::new(),
+            schulte: SchulteTable::new(&conf.schulte),
+            conf,
+        };
+        app.load_clips();
+        if let Some(lesson) = app.overrides.lesson() {
+            app.open_file(lesson);
+        }
+        app
+    }
+
+    // recordings may have changed together with the list
+    fn load_clips(&mut self) {
+        if self.conf.audio_clips {
+            self.clips
+                .load(&self.conf.file_path, self.conf.idx.max(0) as usize);
+        } else {
+            self.clips.clear_cache();
+        }
+    }
+
+    fn displayed_word(&self, poem_mode: bool) -> Option<&str> {
+        if poem_mode || self.word.text == START_HINT || self.word.text.is_empty() {
+            None
+        } else {
+            Some(&self.word.text)
+        }
+    }
+
+    fn toggle_assessment(&mut self) {
+        if self.assessment.is_running() {
+            self.finish_assessment();
+        } else {
+            self.assessment.start(self.conf.assessment_seconds);
+            let _ = self.step_tx.send(StepDir::FORWARD);
+        }
+    }
+
+    fn finish_assessment(&mut self) {
+        if let Some(result) = self.assessment.finish(&self.conf.file_path) {
+            self.history.assessments.push(result.clone());
+            self.history.store_for(&self.profiles.active);
+            self.assessment_result = Some(result);
+        }
+    }
+
+    fn assessment_keys(&mut self, ui: &egui::Ui, poem_mode: bool) {
+        // right arrow: read correctly, F: error, left arrow: take back the last mark
+        let correct = ui.input(|i| i.key_pressed(egui::Key::ArrowRight));
+        let wrong = ui.input(|i| i.key_pressed(egui::Key::F));
+        if !self.assessment.is_running() {
+            return;
+        }
+        if correct || wrong {
+            if let Some(word) = self.displayed_word(poem_mode).map(|w| w.to_string()) {
+                self.assessment.mark(&word, correct);
+            }
+            let _ = self.step_tx.send(StepDir::FORWARD);
+        }
+        if ui.input(|i| i.key_pressed(egui::Key::ArrowLeft)) {
+            self.assessment.undo();
+            let _ = self.step_tx.send(StepDir::BACKWARD);
+            self.session.step_backward();
+        }
+    }
+
+    fn toggle_reaction(&mut self) {
+        if self.reaction.is_running() {
+            self.finish_reaction();
+        } else {
+            self.reaction.start();
+            let _ = self.step_tx.send(StepDir::FORWARD);
+        }
+    }
+
+    fn finish_reaction(&mut self) {
+        if let Some(average) = self.reaction.rolling_average(self.conf.reaction_window) {
+            if self.conf.adapt_rate {
+                self.conf.rate = adapted_rate(average).round();
+            }
+        }
+        let latencies = self.reaction.finish();
+        if !latencies.is_empty() {
+            for (word, latency) in latencies {
+                self.history.record_latency(&word, latency);
+            }
+            self.history.store_for(&self.profiles.active);
+        }
+    }
+
+    fn reaction_keys(&mut self, ui: &egui::Ui, poem_mode: bool) {
+        // while measuring, space confirms the word and escape ends the exercise
+        if !self.reaction.is_running() {
+            return;
+        }
+        if ui.input(|i| i.key_released(egui::Key::Space)) {
+            if let Some(word) = self.displayed_word(poem_mode).map(|w| w.to_string()) {
+                if self.reaction.confirm(&word) {
+                    let _ = self.step_tx.send(StepDir::FORWARD);
+                }
+            }
+        }
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.finish_reaction();
+        }
+    }
+
+    fn poll_engine(&mut self, ctx: &egui::Context) {
+        // progress first: the background thread writes the word before counting it
+        let (position, words_shown, wraps) = match self.progress_lock.read() {
+            Ok(read_guard) => (
+                read_guard.position,
+                read_guard.words_shown,
+                read_guard.wraps,
+            ),
+            Err(_) => return,
+        };
+        if let Ok(read_guard) = self.word_lock.read() {
+            self.word = read_guard.clone()
+        }
+
+        self.conf.idx = position as isize;
+        let shown = words_shown - self.words_shown;
+        if shown > 0 {
+            let key = self.conf.file_key();
+            self.conf.positions.insert(key, position);
+            self.reaction.word_shown();
+            self.tachistoscope
+                .word_shown(&self.word.text, self.conf.tachistoscope.mask);
+            self.peripheral.next_position(&self.conf.peripheral);
+            self.flashcards.card_shown();
+            self.pictures.card_shown();
+            self.spelling.word_shown();
+            // a scrolling text is not read aloud
+            let poem_mode = self.mode_lock.read().map(|m| *m).unwrap_or(false);
+            if !poem_mode {
+                if self.conf.audio_clips {
+                    self.clips.decode_ahead(position);
+                }
+                let clip_played = self.conf.audio_clips
+                    && self.word.audio.as_ref().is_some_and(|p| self.clips.play(p));
+                // a recording replaces the synthesized voice
+                let spoken = if clip_played { "" } else { &self.word.text };
+                self.speech.word_shown(spoken, &self.conf.speech);
+
+                let wrapped = wraps != self.wraps;
+                if let Some(cue) = self
+                    .cues
+                    .for_word(&self.conf.cues, &self.word.text, wrapped)
+                {
+                    self.clips.cue(cue, self.conf.cues.volume);
+                }
+            }
+        }
+        if self.conf.audio_clips {
+            self.clips.update();
+        }
+        if self.speech.update(&self.conf.speech) {
+            ctx.request_repaint_after(std::time::Duration::from_millis(50));
+        }
+        self.session.words_shown(shown);
+        self.words_shown = words_shown;
+
+        let text_passed = std::mem::take(&mut self.text_passes) > 0;
+        let wrapped_file = wraps != self.wraps || text_passed;
+        if self.conf.exercise == Exercise::Ramp && self.running {
+            let chapter_end = wrapped_file || (shown > 0 && self.word.text.trim().is_empty());
+            let rate = self.ramp.update(&self.conf.ramp, shown, chapter_end);
+            self.session.ramp_rate(rate);
+        }
+        if wrapped_file {
+            if let Some(lesson) = &mut self.lesson {
+                lesson.file_finished(Instant::now());
+                if lesson.is_paused() {
+                    self.running = false;
+                }
+            } else if self.playlist.len() > 1 {
+                self.next_in_playlist();
+            }
+        }
+        self.wraps = wraps;
+
+        if self.tachistoscope.is_running() {
+            let frame_time = ctx.input(|i| i.predicted_dt);
+            if self
+                .tachistoscope
+                .update(&self.conf.tachistoscope, frame_time)
+            {
+                let _ = self.step_tx.send(StepDir::FORWARD);
+            }
+            ctx.request_repaint();
+        }
+
+        if self.pictures.is_running() {
+            if self.pictures.update(&self.conf.pictures) {
+                let _ = self.step_tx.send(StepDir::FORWARD);
+            }
+            ctx.request_repaint_after(std::time::Duration::from_millis(50));
+        }
+    }
+
+    fn toggle_tachistoscope(&mut self) {
+        if self.tachistoscope.is_running() {
+            self.tachistoscope.stop();
+        } else {
+            self.tachistoscope.start();
+            let _ = self.step_tx.send(StepDir::FORWARD);
+        }
+    }
+
+    fn toggle_flashcards(&mut self) {
+        if self.flashcards.is_running() {
+            self.flashcards.stop();
+        } else {
+            self.flashcards.start();
+            let _ = self.step_tx.send(StepDir::FORWARD);
+        }
+    }
+
+    fn toggle_pictures(&mut self) {
+        if self.pictures.is_running() {
+            self.pictures.stop();
+        } else {
+            self.pictures.start();
+            let _ = self.step_tx.send(StepDir::FORWARD);
+        }
+    }
+
+    fn toggle_spelling(&mut self) {
+        if self.spelling.is_running() {
+            self.spelling.stop();
+        } else {
+            self.spelling.start();
+            let _ = self.step_tx.send(StepDir::FORWARD);
+        }
+    }
+
+    fn spelling_keys(&mut self, ui: &egui::Ui) {
+        // the typed word is checked, enter continues with the next one
+        if self.spelling.result().is_some() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            let _ = self.step_tx.send(StepDir::FORWARD);
+        }
+    }
+
+    fn flashcard_keys(&mut self, ui: &egui::Ui) {
+        // J: known, N: not known yet; the card counts for the word on its front
+        let known = ui.input(|i| i.key_pressed(egui::Key::J));
+        let unknown = ui.input(|i| i.key_pressed(egui::Key::N));
+        if (known || unknown) && self.flashcards.mark(known) {
+            let stats = self
+                .history
+                .cards
+                .entry(self.word.text.clone())
+                .or_default();
+            if known {
+                stats.known += 1;
+            } else {
+                stats.unknown += 1;
+            }
+            self.history.store_for(&self.profiles.active);
+            let _ = self.step_tx.send(StepDir::FORWARD);
+        }
+    }
+
+    fn settings_window(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Einstellungen")
+            .open(&mut self.show_settings)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("exercise_settings").show(ui, |ui| {
+                    ui.label("Dauer Lesetest:");
+                    ui.add(
+                        DragValue::new(&mut self.conf.assessment_seconds)
+                            .range(10.0..=600.0)
+                            .suffix(" s"),
+                    );
+                    ui.end_row();
+
+                    ui.label("Karteikarten:");
+                    ui.add(
+                        DragValue::new(&mut self.conf.flashcard_front_ms)
+                            .range(0..=60000)
+                            .prefix("Rückseite nach ")
+                            .suffix(" ms"),
+                    );
+                    ui.end_row();
+
+                    ui.label("Aufnahmen:");
+                    ui.checkbox(&mut self.conf.audio_clips, "Abspielen, wenn das Wort erscheint")
+                        .on_hover_text("Sucht wort.ogg oder wort.wav neben der Liste oder im Ordner mit dem Namen der Liste.");
+                    ui.end_row();
+
+                    let cues = &mut self.conf.cues;
+                    ui.label("Töne:");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut cues.tick, "Wortwechsel");
+                        ui.checkbox(&mut cues.sentence_end, "Satzende");
+                        ui.checkbox(&mut cues.wrap, "Dateiende");
+                    });
+                    ui.end_row();
+                    ui.label("");
+                    ui.add(egui::Slider::new(&mut cues.volume, 0.0..=1.0).text("Lautstärke"));
+                    ui.end_row();
+
+                    let speech = &mut self.conf.speech;
+                    ui.label("Sprachausgabe:");
+                    egui::ComboBox::from_id_salt("speech_timing")
+                        .selected_text(speech.timing.label())
+                        .show_ui(ui, |ui| {
+                            for timing in [SpeechTiming::Off, SpeechTiming::Before, SpeechTiming::After, SpeechTiming::Instead] {
+                                ui.selectable_value(&mut speech.timing, timing, timing.label());
+                            }
+                        });
+                    ui.end_row();
+                    ui.label("");
+                    ui.horizontal(|ui| {
+                        ui.label("Stimme");
+                        ui.add(egui::TextEdit::singleline(&mut speech.voice).desired_width(50.0))
+                            .on_hover_text("Sprache bzw. Stimme von espeak-ng, z.B. de oder en");
+                        ui.add(
+                            DragValue::new(&mut speech.delay_ms)
+                                .range(0..=10000)
+                                .prefix("Abstand ")
+                                .suffix(" ms"),
+                        );
+                    });
+                    ui.end_row();
+                    if self.speech.has_failed() {
+                        ui.label("");
+                        ui.colored_label(ui.visuals().error_fg_color, "espeak-ng wurde nicht gefunden");
+                        ui.end_row();
+                    }
+
+                    ui.label("Rechtschreibung:");
+                    ui.add(
+                        DragValue::new(&mut self.conf.spelling_flash_ms)
+                            .range(50..=10000)
+                            .prefix("Wort ")
+                            .suffix(" ms"),
+                    );
+                    ui.end_row();
+
+                    let pictures = &mut self.conf.pictures;
+                    ui.label("Bildkarten:");
+                    egui::ComboBox::from_id_salt("picture_placement")
+                        .selected_text(pictures.placement.label())
+                        .show_ui(ui, |ui| {
+                            for placement in [Placement::Above, Placement::Instead, Placement::PictureFirst] {
+                                ui.selectable_value(&mut pictures.placement, placement, placement.label());
+                            }
+                        });
+                    ui.end_row();
+                    ui.label("");
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            DragValue::new(&mut pictures.picture_ms)
+                                .range(100..=60000)
+                                .prefix("Bild ")
+                                .suffix(" ms"),
+                        );
+                        ui.add_enabled(
+                            pictures.placement == Placement::PictureFirst,
+                            DragValue::new(&mut pictures.word_ms)
+                                .range(100..=60000)
+                                .prefix("Wort ")
+                                .suffix(" ms"),
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label("Reaktionszeit:");
+                    ui.checkbox(&mut self.conf.adapt_rate, "Frequenz anpassen")
+                        .on_hover_text("Setzt die Frequenz nach der Übung auf das Mittel der letzten Erkennungszeiten.");
+                    ui.end_row();
+                    ui.label("Mittel über:");
+                    ui.add(
+                        DragValue::new(&mut self.conf.reaction_window)
+                            .range(1..=100)
+                            .suffix(" Wörter"),
+                    );
+                    ui.end_row();
+
+                    let ramp = &mut self.conf.ramp;
+                    ui.label("Tempotraining:");
+                    ui.horizontal(|ui| {
+                        ui.add(DragValue::new(&mut ramp.start).range(10.0..=800.0));
+                        ui.label("→");
+                        ui.add(
+                            DragValue::new(&mut ramp.target)
+                                .range(10.0..=800.0)
+                                .suffix(" wpm"),
+                        );
+                    });
+                    ui.end_row();
+                    ui.label("Erhöhung:");
+                    ui.horizontal(|ui| {
+                        ui.add(DragValue::new(&mut ramp.step).range(1.0..=100.0).prefix("+"));
+                        ui.label("alle");
+                        ui.add(DragValue::new(&mut ramp.every).range(1..=1000));
+                        egui::ComboBox::from_id_salt("ramp_unit")
+                            .selected_text(match ramp.unit {
+                                RampUnit::Words => "Wörter",
+                                RampUnit::Seconds => "Sekunden",
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut ramp.unit, RampUnit::Words, "Wörter");
+                                ui.selectable_value(&mut ramp.unit, RampUnit::Seconds, "Sekunden");
+                            });
+                    });
+                    ui.end_row();
+                    ui.label("");
+                    ui.checkbox(&mut ramp.drop_back, "Nach jedem Kapitel neu beginnen")
+                        .on_hover_text("Ein Kapitel endet bei einer Leerzeile oder am Ende der Datei.");
+                    ui.end_row();
+
+                    // independent of the rate, the phases are timed per frame
+                    let t = &mut self.conf.tachistoscope;
+                    ui.label("Tachistoskop:");
+                    ui.add(
+                        DragValue::new(&mut t.exposure_ms)
+                            .range(10..=5000)
+                            .prefix("Wort ")
+                            .suffix(" ms"),
+                    );
+                    ui.end_row();
+                    ui.label("");
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            DragValue::new(&mut t.mask_ms)
+                                .range(0..=5000)
+                                .prefix("Maske ")
+                                .suffix(" ms"),
+                        );
+                        ui.selectable_value(&mut t.mask, MaskStyle::Hashes, "#####");
+                        ui.selectable_value(&mut t.mask, MaskStyle::RandomLetters, "xqzjt");
+                    });
+                    ui.end_row();
+                    ui.label("");
+                    ui.add(
+                        DragValue::new(&mut t.blank_ms)
+                            .range(0..=10000)
+                            .prefix("Pause ")
+                            .suffix(" ms"),
+                    );
+                    ui.end_row();
+
+                    let p = &mut self.conf.peripheral;
+                    ui.label("Blickspanne:");
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            DragValue::new(&mut p.eccentricity)
+                                .range(20.0..=1000.0)
+                                .prefix("Abstand ")
+                                .suffix(" px"),
+                        );
+                        egui::ComboBox::from_id_salt("peripheral_pattern")
+                            .selected_text(p.pattern.label())
+                            .show_ui(ui, |ui| {
+                                for pattern in [Pattern::Random, Pattern::Clockwise, Pattern::LeftRight] {
+                                    ui.selectable_value(&mut p.pattern, pattern, pattern.label());
+                                }
+                            });
+                    });
+                    ui.end_row();
+                    ui.label("");
+                    ui.horizontal(|ui| {
+                        ui.add(DragValue::new(&mut p.seed).prefix("Seed "))
+                            .on_hover_text("Mit dem gleichen Seed erscheinen die Wörter an den gleichen Stellen.");
+                        if ui.small_button("🎲").clicked() {
+                            p.seed = rand::random_range(0..100_000);
+                        }
+                    });
+                    ui.end_row();
+                });
+            });
+    }
+
+    fn finish_session(&mut self) {
+        if let Some(session) = self
+            .session
+            .finish(&self.profiles.active, &self.conf.file_path)
+        {
+            self.history.sessions.push(session);
+            self.history.store_for(&self.profiles.active);
         }
     }
+
+    fn reload(&mut self) {
+        self.finish_session();
+        self.finish_assessment();
+        self.finish_reaction();
+        self.tachistoscope.stop();
+        self.flashcards.stop();
+        self.pictures.stop();
+        self.spelling.stop();
+        self.speech.stop();
+        self.clips.stop();
+        self.load_clips();
+        self.running = false;
+        self.scroll_offset = 0.0;
+        self.text_passes = 0;
+        self.word = Entry::new(START_HINT);
+        if let Ok(mut guard) = self.word_lock.write() {
+            *guard = self.word.clone();
+        }
+        let _ = self.load_tx.send(LoadRequest::from_settings(&self.conf));
+        if self.conf.exercise == Exercise::Ramp {
+            self.ramp.reset(&self.conf.ramp);
+        }
+        self.peripheral = Peripheral::new(self.conf.peripheral.seed);
+        if self.conf.file_path.is_file() {
+            add_recent(&mut self.conf.recent_files, &self.conf.file_path);
+        }
+        self.watch = FileWatch::new(&self.conf.file_path);
+        self.analyze_file();
+    }
+
+    // continues where the file was left
+    fn open_file(&mut self, path: PathBuf) {
+        if is_lesson(&path) {
+            self.start_lesson(&path);
+            return;
+        }
+        if let Some(lesson) = self.lesson.take() {
+            lesson.restore(&mut self.conf);
+        }
+        self.conf.idx = self.conf.stored_position(&path);
+        self.conf.apply_front_matter(&read_front_matter(&path));
+        self.conf.file_path = path;
+        self.playlist.clear();
+        println!("opening {}", self.conf.file_path.display());
+        self.reload();
+    }
+
+    // keeps running, only the file changes
+    fn next_in_playlist(&mut self) {
+        self.finish_session();
+        self.playlist_pos = (self.playlist_pos + 1) % self.playlist.len();
+        self.conf.file_path = self.playlist[self.playlist_pos].clone();
+        self.conf.idx = 0;
+        self.conf
+            .apply_front_matter(&read_front_matter(&self.conf.file_path));
+        println!("next file in playlist: {}", self.conf.file_path.display());
+        add_recent(&mut self.conf.recent_files, &self.conf.file_path);
+        self.load_clips();
+        let _ = self.load_tx.send(LoadRequest::from_settings(&self.conf));
+        self.watch = FileWatch::new(&self.conf.file_path);
+        self.analyze_file();
+    }
+
+    fn start_lesson(&mut self, path: &Path) {
+        match Lesson::load(path) {
+            Ok(lesson) => {
+                // a running lesson gives back the learner's settings first
+                if let Some(previous) = self.lesson.take() {
+                    previous.restore(&mut self.conf);
+                }
+                println!("starting lesson {}", lesson.title);
+                let run = LessonRun::new(lesson, &self.conf);
+                run.apply(&mut self.conf, &read_front_matter(&run.current().file));
+                self.lesson = Some(run);
+                self.playlist.clear();
+                self.reload();
+            }
+            Err(err) => println!("could not load lesson {}: {}", path.display(), err),
+        }
+    }
+
+    fn stop_lesson(&mut self) {
+        if let Some(lesson) = self.lesson.take() {
+            lesson.restore(&mut self.conf);
+            self.reload();
+        }
+    }
+
+    // reads the file again without stopping, the reader stays at the same word
+    fn hot_reload(&mut self, ctx: &egui::Context) {
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        if !self.watch.changed() {
+            return;
+        }
+        println!("{} changed, reloading", self.conf.file_path.display());
+        self.load_clips();
+        let _ = self.load_tx.send(LoadRequest {
+            word: Some(self.word.text.clone()).filter(|w| w != START_HINT),
+            ..LoadRequest::from_settings(&self.conf)
+        });
+        self.analyze_file();
+        self.reloaded_at = Some(Instant::now());
+    }
+
+    fn update_lesson(&mut self, ctx: &egui::Context) {
+        let now = Instant::now();
+        let Some(lesson) = &mut self.lesson else {
+            return;
+        };
+        if lesson.update(now) {
+            let header = read_front_matter(&lesson.current().file);
+            lesson.apply(&mut self.conf, &header);
+            self.reload();
+            self.running = true;
+        } else if let Some(remaining) = lesson.remaining_break(now) {
+            ctx.request_repaint_after(remaining.min(std::time::Duration::from_secs(1)));
+        }
+    }
+
+    fn lesson_screen(&mut self, ui: &mut egui::Ui) {
+        let Some(lesson) = &mut self.lesson else {
+            return;
+        };
+        let mut next = false;
+        let mut stop = false;
+        ui.vertical_centered(|ui| match lesson.state {
+            LessonState::Break { .. } => {
+                let remaining = lesson.remaining_break(Instant::now()).unwrap_or_default();
+                ui.label(RichText::new("Pause").size(self.conf.font_size).strong());
+                ui.label(
+                    RichText::new(format!("{} s", remaining.as_secs_f32().ceil()))
+                        .size(self.conf.font_size * 0.6),
+                );
+                ui.add_space(20.0);
+                let upcoming = &lesson.lesson.items[lesson.item + 1];
+                ui.label(format!(
+                    "Als Nächstes: {}",
+                    upcoming
+                        .file
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                ));
+                ui.add_space(10.0);
+                next = ui.button("Weiter").clicked();
+            }
+            LessonState::Done => {
+                ui.label(
+                    RichText::new("Geschafft!")
+                        .size(self.conf.font_size)
+                        .strong(),
+                );
+                ui.label(format!("Lektion «{}» beendet", lesson.lesson.title));
+                ui.add_space(10.0);
+                stop = ui.button("Lektion schliessen").clicked();
+            }
+            LessonState::Reading => {}
+        });
+        if next {
+            lesson.next_item();
+            let header = read_front_matter(&lesson.current().file);
+            lesson.apply(&mut self.conf, &header);
+            self.reload();
+            self.running = true;
+        }
+        if stop {
+            self.stop_lesson();
+        }
+    }
+
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let mut dropped: Vec<PathBuf> = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter_map(|f| f.path.clone())
+                .collect()
+        });
+        if dropped.is_empty() {
+            return;
+        }
+        if dropped.len() == 1 && is_lesson(&dropped[0]) {
+            self.open_file(dropped.remove(0));
+            return;
+        }
+        if let Some(lesson) = self.lesson.take() {
+            lesson.restore(&mut self.conf);
+        }
+        // the order of several dropped files depends on the system, so sort them by name
+        dropped.sort();
+        self.conf.file_path = dropped[0].clone();
+        self.conf.idx = 0;
+        self.conf
+            .apply_front_matter(&read_front_matter(&self.conf.file_path));
+        self.playlist = dropped;
+        self.playlist_pos = 0;
+        println!("opening a dropped file");
+        self.reload();
+    }
+
+    fn drop_overlay(&self, ctx: &egui::Context) {
+        let hovered = ctx.input(|i| i.raw.hovered_files.len());
+        if hovered == 0 {
+            return;
+        }
+        let text = if hovered == 1 {
+            "Datei hier ablegen".to_string()
+        } else {
+            format!("{} Dateien nacheinander lesen", hovered)
+        };
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("drop_overlay"),
+        ));
+        let rect = ctx.content_rect();
+        painter.rect_filled(rect, 0.0, egui::Color32::from_black_alpha(192));
+        painter.text(
+            rect.center(),
+            Align2::CENTER_CENTER,
+            text,
+            FontId::proportional(32.0),
+            egui::Color32::WHITE,
+        );
+    }
+
+    // the rate the engine and the scrolling text use
+    fn rate(&self) -> f32 {
+        if self.conf.exercise == Exercise::Ramp {
+            self.ramp.rate
+        } else {
+            self.conf.rate
+        }
+    }
+
+    // the speed ramp has its own rate, the learner's reading rate stays as it is
+    fn rate_mut(&mut self) -> &mut f32 {
+        if self.conf.exercise == Exercise::Ramp {
+            &mut self.ramp.rate
+        } else {
+            &mut self.conf.rate
+        }
+    }
+
+    fn analyze_file(&mut self) {
+        self.file_info = crate::analysis::load(
+            &self.conf.file_path,
+            self.conf.mode,
+            self.conf.language,
+            self.conf.rate,
+        );
+    }
+
+    fn switch_profile(&mut self, name: &str) {
+        if let Some(lesson) = self.lesson.take() {
+            lesson.restore(&mut self.conf);
+        }
+        self.finish_session();
+        save_settings(&self.profiles.active, &self.overrides.persisted(&self.conf));
+        // the command line options only apply to the profile they were given for
+        self.overrides = Overrides::none();
+        self.conf = load_settings(name);
+        self.history = History::load_for(name);
+        self.profiles.add(name);
+        self.profiles.active = name.to_string();
+        self.profiles.store();
+        println!("switching to profile {}", name);
+        self.reload();
+    }
+
+    fn profile_window(&mut self, ctx: &egui::Context) {
+        let mut selected = None;
+        let mut open = true;
+        egui::Window::new("Wer liest?")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    for name in self.profiles.names.iter() {
+                        let active = *name == self.profiles.active;
+                        if ui
+                            .selectable_label(active, RichText::new(name).size(20.0))
+                            .clicked()
+                        {
+                            selected = Some(name.clone());
+                        }
+                    }
+                });
+                ui.add_space(10.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.new_profile_name);
+                    let name = self.new_profile_name.trim().to_string();
+                    if ui
+                        .add_enabled(is_valid_name(&name), egui::Button::new("Neu"))
+                        .on_hover_text("Erlaubt sind Buchstaben, Zahlen, Leerzeichen und -")
+                        .clicked()
+                    {
+                        selected = Some(name);
+                        self.new_profile_name.clear();
+                    }
+                });
+            });
+        if let Some(name) = selected {
+            if name != self.profiles.active {
+                self.switch_profile(&name);
+            }
+            open = false;
+        }
+        self.show_profiles = open;
+    }
+
+    fn difficult_words_panel(&mut self, ctx: &egui::Context) {
+        egui::SidePanel::right("difficult_words")
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.add_space(10.0);
+                ui.label(RichText::new("Schwierige Wörter").size(20.0).strong());
+                ui.add_space(10.0);
+
+                let mut removed = None;
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() - 50.0)
+                    .show(ui, |ui| {
+                        for word in self.conf.difficult_words() {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("✖").clicked() {
+                                    removed = Some(word.clone());
+                                }
+                                ui.label(RichText::new(word).size(16.0));
+                            });
+                        }
+                    });
+                if let Some(word) = removed {
+                    self.conf.toggle_difficult_word(&word);
+                }
+
+                ui.add_space(10.0);
+                let words = self.conf.difficult_words().to_vec();
+                if ui
+                    .add_enabled(!words.is_empty(), egui::Button::new("Exportieren"))
+                    .on_hover_text("Speichert die Wörter als neue Wortliste.")
+                    .clicked()
+                {
+                    let file_name = match self.conf.file_path.file_stem() {
+                        Some(stem) => format!("{}_schwierig.txt", stem.to_string_lossy()),
+                        None => "schwierig.txt".to_string(),
+                    };
+                    if let Some(path) = rfd::FileDialog::new().set_file_name(file_name).save_file()
+                    {
+                        if let Err(err) = write_words_to_file(&path, &words) {
+                            println!("exporting difficult words failed: {:?}", err);
+                        }
+                    }
+                }
+            });
+    }
 }
 
 impl eframe::App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_engine(ctx);
+        self.hot_reload(ctx);
+        self.update_lesson(ctx);
+        self.handle_dropped_files(ctx);
+        self.drop_overlay(ctx);
+        if self.show_profiles {
+            self.profile_window(ctx);
+        }
+        if self.show_difficult_words {
+            self.difficult_words_panel(ctx);
+        }
+        if self.show_library {
+            if let Some(path) = library_panel(
+                ctx,
+                &mut self.library,
+                &mut self.conf.library_folder,
+                &self.conf.positions,
+                &self.conf.file_path,
+            ) {
+                self.open_file(path);
+            }
+        }
+        if self.show_settings {
+            self.settings_window(ctx);
+        }
+        assessment_result_window(ctx, &mut self.assessment_result);
+        if self.show_schulte {
+            if let Some(result) = schulte_window(
+                ctx,
+                &mut self.show_schulte,
+                &mut self.conf.schulte,
+                &mut self.schulte,
+                self.conf.font_size,
+            ) {
+                self.history.schulte.push(result);
+                self.history.store_for(&self.profiles.active);
+            }
+        }
+        if self.show_stats {
+            statistics_window(
+                ctx,
+                &mut self.show_stats,
+                &self.profiles.active,
+                &self.history,
+            );
+        }
+        if self.show_file_info
+            && file_info_window(
+                ctx,
+                &mut self.show_file_info,
+                self.file_info.as_ref(),
+                &mut self.conf.language,
+                self.conf.rate,
+            )
+        {
+            self.analyze_file();
+        }
+        // keyboard shortcuts must not fire while typing or choosing a learner
+        let keys_enabled = !self.show_profiles && !ctx.wants_keyboard_input();
+
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.add_space(ui.available_size().y * 0.3);
-            if let Ok(read_guard) = self.word_lock.read() {
-                self.word = read_guard.clone()
-            }
 
             let poem_mode = if let Ok(read_guard) = self.mode_lock.read() {
                 *read_guard
@@ -98,7 +1316,9 @@ impl eframe::App for MyApp {
                 false
             };
 
-            if poem_mode && self.running {
+            if self.lesson.as_ref().is_some_and(|l| l.is_paused()) {
+                self.lesson_screen(ui);
+            } else if poem_mode && self.running {
                 // Scrolling animation: move the single-line text from left to right
                 let avail = ui.available_size();
                 let height = (self.conf.font_size * 1.4).max(30.0);
@@ -110,22 +1330,18 @@ impl eframe::App for MyApp {
                 let dt = now.duration_since(self.last_instant).as_secs_f32();
                 self.last_instant = now;
 
-                // Speed: use conf.rate as pixels/sec
-                let speed_px_per_sec = self.conf.rate;
-                self.scroll_offset -= speed_px_per_sec * dt;
+                // Speed: use the rate as pixels/sec
+                let speed_px_per_sec = self.rate();
 
                 // Approximate text pixel width
                 let char_width_est = self.conf.font_size * 0.6; // heuristic
-                let text_px_width = (self.word.chars().count() as f32) * char_width_est;
+                let text_px_width = (self.word.text.chars().count() as f32) * char_width_est;
                 let gap = rect.width().max(40.0); // gap between repetitions
                 let total_cycle = text_px_width + gap;
 
-                if total_cycle > 0.0 {
-                    // Wrap offset so it loops
-                    self.scroll_offset = self.scroll_offset % total_cycle;
-                } else {
-                    self.scroll_offset = 0.0;
-                }
+                // the end of the text counts like the end of a word list
+                self.text_passes +=
+                    scroll_text(&mut self.scroll_offset, speed_px_per_sec * dt, total_cycle);
 
                 // Compute base x so text moves left-to-right so text starts centered when scroll_offset = 0.0
                 let base_x = rect.center().x + self.scroll_offset;
@@ -139,7 +1355,7 @@ impl eframe::App for MyApp {
                 clipped_painter.text(
                     Pos2::new(base_x, y),
                     Align2::LEFT_TOP,
-                    &self.word,
+                    &self.word.text,
                     FontId::new(self.conf.font_size, FontFamily::Name("my_font".into())),
                     ui.style().visuals.strong_text_color(),
                 );
@@ -148,29 +1364,147 @@ impl eframe::App for MyApp {
                 clipped_painter.text(
                     Pos2::new(base_x + total_cycle, y),
                     Align2::LEFT_TOP,
-                    &self.word,
+                    &self.word.text,
                     FontId::new(self.conf.font_size, FontFamily::Name("my_font".into())),
                     ui.style().visuals.strong_text_color(),
                 );
 
                 ctx.request_repaint();
 
+            } else if self.conf.exercise == Exercise::Peripheral && self.running {
+                self.last_instant = Instant::now();
+
+                // keep the eyes on the cross, the words appear around it
+                let height = (self.conf.font_size * 1.4).max(30.0);
+                let (rect, _response) =
+                    ui.allocate_exact_size(Vec2::new(ui.available_width(), height), Sense::hover());
+                let font_id = FontId::new(self.conf.font_size, FontFamily::Name("my_font".into()));
+                ui.painter().text(
+                    rect.center(),
+                    Align2::CENTER_CENTER,
+                    "+",
+                    font_id.clone(),
+                    ui.style().visuals.weak_text_color(),
+                );
+                if let Some(word) = self.displayed_word(poem_mode) {
+                    ui.painter().text(
+                        rect.center() + self.peripheral.offset(),
+                        Align2::CENTER_CENTER,
+                        word,
+                        font_id,
+                        ui.style().visuals.strong_text_color(),
+                    );
+                }
             } else {
                 self.last_instant = Instant::now();
 
-                if self.word.chars().count() >= 10 {
-                    ui.vertical_centered(|ui| {
-                        let font_id =
-                            FontId::new(self.conf.font_size, FontFamily::Name("my_font".into()));
-                        ui.label(RichText::new("Drücke auf Start...").font(font_id).strong());
-                    });
+                // the screen stays empty during a pause
+                let mut text = if self.word.markup.pause.is_some() {
+                    ""
                 } else {
-                    ui.vertical_centered(|ui| {
+                    self.displayed_word(poem_mode).unwrap_or(START_HINT)
+                };
+                if self.tachistoscope.is_running() {
+                    text = self.tachistoscope.display(text);
+                }
+                let text = text.to_string();
+                // dictation: the word is only heard, or heard before it is seen
+                let spoken_only = self.displayed_word(poem_mode).is_some()
+                    && !self.speech.word_visible(&self.conf.speech);
+                ui.vertical_centered(|ui| {
+                    let mut show_word = !spoken_only;
+                    if self.pictures.is_running() {
+                        // words without a picture are shown as they are
+                        if let Some(path) = &self.word.image {
+                            let view = self.pictures.view(&self.conf.pictures);
+                            if view.picture {
+                                // the loaders cache the decoded image per uri
+                                ui.add(
+                                    egui::Image::new(format!("file://{}", path.display()))
+                                        .max_size(Vec2::splat(self.conf.font_size * 4.0)),
+                                );
+                            }
+                            show_word &= view.word;
+                        }
+                    }
+                    let flash_ms = self.conf.spelling_flash_ms;
+                    if self.spelling.is_hidden(flash_ms) {
+                        show_word = false;
+                    }
+                    if show_word {
+                        let markup = &self.word.markup;
+                        let size = if markup.emphasis {
+                            self.conf.font_size * 1.2
+                        } else {
+                            self.conf.font_size
+                        };
+                        let font_id = FontId::new(size, FontFamily::Name("my_font".into()));
+                        let mut word = RichText::new(text).font(font_id).strong();
+                        if markup.emphasis {
+                            word = word.underline();
+                        }
+                        if let Some([r, g, b]) = markup.color {
+                            word = word.color(egui::Color32::from_rgb(r, g, b));
+                        }
+                        ui.label(word);
+                    }
+
+                    if self.spelling.is_running() {
                         let font_id =
                             FontId::new(self.conf.font_size, FontFamily::Name("my_font".into()));
-                        ui.label(RichText::new(&self.word).font(font_id).strong());
-                    });
-                }
+                        match self.spelling.time_to_hide(flash_ms) {
+                            Some(remaining) => ctx.request_repaint_after(remaining),
+                            None if self.spelling.is_hidden(flash_ms) => {
+                                match self.spelling.result() {
+                                    Some(marks) => {
+                                        ui.label(diff_layout(marks, font_id, ui.visuals()));
+                                    }
+                                    None => {
+                                        let response = ui.add(
+                                            egui::TextEdit::singleline(&mut self.spelling.input)
+                                                .font(font_id)
+                                                .horizontal_align(egui::Align::Center)
+                                                .desired_width(ui.available_width() * 0.6),
+                                        );
+                                        if response.lost_focus()
+                                            && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                                        {
+                                            let accuracy = self.spelling.check(&self.word.text);
+                                            self.history.record_spelling(&self.word.text, accuracy);
+                                            self.history.store_for(&self.profiles.active);
+                                            // the same enter must not skip to the next word
+                                            ui.input_mut(|i| {
+                                                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter)
+                                            });
+                                        } else {
+                                            response.request_focus();
+                                        }
+                                    }
+                                }
+                            }
+                            None => {}
+                        }
+                    }
+
+                    if self.flashcards.is_running() {
+                        match self.flashcards.time_to_reveal(self.conf.flashcard_front_ms) {
+                            Some(remaining) => ctx.request_repaint_after(remaining),
+                            None if self.flashcards.is_revealed(self.conf.flashcard_front_ms) => {
+                                let back = self.word.back.as_deref().unwrap_or("–");
+                                let font_id = FontId::new(
+                                    self.conf.font_size * 0.7,
+                                    FontFamily::Name("my_font".into()),
+                                );
+                                ui.label(
+                                    RichText::new(back)
+                                        .font(font_id)
+                                        .color(ui.visuals().selection.bg_fill),
+                                );
+                            }
+                            None => {}
+                        }
+                    }
+                });
             }
             ui.vertical_centered(|ui| {
                 ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
@@ -178,20 +1512,84 @@ impl eframe::App for MyApp {
 
                     if ui.button("Datei öffnen").clicked() {
                         match rfd::FileDialog::new().pick_file() {
-                            Some(path) => {
-                                self.conf.file_path = path;
+                            Some(path) => self.open_file(path),
+                            None => {
+                                self.conf.file_path = PathBuf::new();
+                                self.conf.idx = 0;
+                                self.playlist.clear();
+                                self.reload();
                             }
-                            None => self.conf.file_path = PathBuf::new(),
                         }
+                    }
 
-                        self.running = false;
-                        self.word = "Drücke auf Start...".to_string();
-                        if let Ok(mut guard) = self.word_lock.write() {
-                            *guard = self.word.clone();
+                    let mut recent = None;
+                    ui.add_enabled_ui(!self.conf.recent_files.is_empty(), |ui| {
+                        ui.menu_button("Zuletzt geöffnet", |ui| {
+                            for path in &self.conf.recent_files {
+                                let name = path
+                                    .file_name()
+                                    .map(|n| n.to_string_lossy().to_string())
+                                    .unwrap_or_default();
+                                if ui
+                                    .button(name)
+                                    .on_hover_text(path.display().to_string())
+                                    .clicked()
+                                {
+                                    recent = Some(path.clone());
+                                    ui.close();
+                                }
+                            }
+                        });
+                    });
+                    if let Some(path) = recent {
+                        self.open_file(path);
+                    }
+
+                    let mut stop_lesson = false;
+                    if let Some(lesson) = &self.lesson {
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "Lektion «{}»: {} von {}, Runde {} von {}",
+                                lesson.lesson.title,
+                                lesson.item + 1,
+                                lesson.lesson.items.len(),
+                                (lesson.repetition + 1).min(lesson.current().repetitions.max(1)),
+                                lesson.current().repetitions.max(1)
+                            ));
+                            stop_lesson = ui
+                                .small_button("✖")
+                                .on_hover_text("Lektion beenden")
+                                .clicked();
+                        });
+                    }
+                    if stop_lesson {
+                        self.stop_lesson();
+                    }
+
+                    if let Some(reloaded_at) = self.reloaded_at {
+                        let shown_for = std::time::Duration::from_secs(3);
+                        if reloaded_at.elapsed() < shown_for {
+                            ui.label(RichText::new("↻ Datei neu geladen").weak());
+                            ctx.request_repaint_after(shown_for - reloaded_at.elapsed());
+                        } else {
+                            self.reloaded_at = None;
                         }
+                    }
 
-                        println!("opening a new file");
-                        let _ = self.load_tx.send(self.conf.file_path.clone());
+                    if self.playlist.len() > 1 {
+                        let names = self
+                            .playlist
+                            .iter()
+                            .filter_map(|p| p.file_name())
+                            .map(|n| n.to_string_lossy().to_string())
+                            .collect::<Vec<String>>()
+                            .join("\n");
+                        ui.label(format!(
+                            "Datei {} von {}",
+                            self.playlist_pos + 1,
+                            self.playlist.len()
+                        ))
+                        .on_hover_text(names);
                     }
 
                     ui.add_space(10.0);
@@ -203,12 +1601,20 @@ impl eframe::App for MyApp {
                     };
 
                     ui.add(
-                        DragValue::new(&mut self.conf.rate)
+                        DragValue::new(self.rate_mut())
                             .fixed_decimals(0)
                             .range(10.0..=800.0)
                             .suffix(suffix),
                     );
 
+                    if self.conf.exercise == Exercise::Ramp {
+                        ui.label(format!(
+                            "↗ {:.0} → {:.0} wpm",
+                            self.conf.ramp.start, self.conf.ramp.target
+                        ))
+                        .on_hover_text("Tempotraining: die Frequenz steigt automatisch.");
+                    }
+
                     ui.add_space(5.0);
 
                     ui.label(RichText::new("Frequenz:").size(20.0).strong());
@@ -224,37 +1630,121 @@ impl eframe::App for MyApp {
                     }
 
 
-                    let b_text = if self.running {
+                    if self.assessment.is_running() {
+                        if self.assessment.is_over() {
+                            self.finish_assessment();
+                        }
+                        ui.label(format!(
+                            "noch {} s   ✔ {}   ✖ {}",
+                            self.assessment.remaining().as_secs(),
+                            self.assessment.correct(),
+                            self.assessment.incorrect()
+                        ));
+                        ui.add_space(5.0);
+                        ctx.request_repaint_after(std::time::Duration::from_millis(200));
+                    }
+                    if self.flashcards.is_running() {
+                        ui.label(format!(
+                            "J: gewusst ✔ {}   N: nicht gewusst ✖ {}",
+                            self.flashcards.known(),
+                            self.flashcards.unknown()
+                        ));
+                        ui.add_space(5.0);
+                    }
+                    if self.spelling.is_running() {
+                        ui.label(match self.spelling.average_accuracy() {
+                            Some(accuracy) => format!(
+                                "⌀ {:.0} % richtig in {} Wörtern   Enter: nächstes Wort",
+                                accuracy,
+                                self.spelling.words()
+                            ),
+                            None => "Wort merken, eintippen und mit Enter prüfen".to_string(),
+                        });
+                        ui.add_space(5.0);
+                    }
+                    if self.reaction.is_running() {
+                        let average = self.reaction.rolling_average(self.conf.reaction_window);
+                        ui.label(match average {
+                            Some(average) => format!(
+                                "⌀ {:.0} ms  → {:.0} wpm",
+                                average,
+                                adapted_rate(average)
+                            ),
+                            None => "Leertaste, sobald das Wort erkannt ist".to_string(),
+                        });
+                        ui.add_space(5.0);
+                    }
+
+                    let b_text = if self.running
+                        || self.assessment.is_running()
+                        || self.reaction.is_running()
+                        || self.tachistoscope.is_running()
+                        || self.flashcards.is_running()
+                        || self.pictures.is_running()
+                        || self.spelling.is_running()
+                    {
                         RichText::new("Stopp").size(20.0).strong()
                     } else {
                         RichText::new("Start").size(20.0).strong()
                     };
 
                     let mut space_pressed = false;
-                    if ui.input(|i| i.key_released(egui::Key::Space)) {
+                    if keys_enabled
+                        && !self.reaction.is_running()
+                        && ui.input(|i| i.key_released(egui::Key::Space))
+                    {
                         space_pressed = true;
                     }
-                    if ui.input(|i| i.key_pressed(egui::Key::ArrowUp) || i.key_pressed(egui::Key::ArrowDown)) {
+                    if keys_enabled && ui.input(|i| i.key_pressed(egui::Key::ArrowUp) || i.key_pressed(egui::Key::ArrowDown)) {
                         let shift = ui.input(|i| i.modifiers.shift);
                         let step = if shift { 10.0 } else { 1.0 };
 
-                        if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) && self.conf.rate <= 800.0 - step {
-                            self.conf.rate += step;
+                        let rate = self.rate_mut();
+                        if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) && *rate <= 800.0 - step {
+                            *rate += step;
                         }
-                        if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) && self.conf.rate >= 10.0 + step {
-                            self.conf.rate -= step;
+                        if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) && *rate >= 10.0 + step {
+                            *rate -= step;
                         }
                     }
 
-                    if ui.input(|i| i.key_pressed(egui::Key::ArrowLeft)) {
-                        let _ = self.step_tx.send(StepDir::BACKWARD);
+                    if keys_enabled && self.conf.exercise == Exercise::Assessment {
+                        self.assessment_keys(ui, poem_mode);
+                    } else if keys_enabled && self.reaction.is_running() {
+                        self.reaction_keys(ui, poem_mode);
+                    } else if keys_enabled && self.flashcards.is_running() {
+                        self.flashcard_keys(ui);
+                    } else if keys_enabled && self.spelling.is_running() {
+                        self.spelling_keys(ui);
+                    } else if keys_enabled {
+                        if ui.input(|i| i.key_pressed(egui::Key::ArrowLeft)) {
+                            let _ = self.step_tx.send(StepDir::BACKWARD);
+                            self.session.step_backward();
+                        }
+                        if ui.input(|i| i.key_pressed(egui::Key::ArrowRight)) {
+                            let _ = self.step_tx.send(StepDir::FORWARD);
+                        }
                     }
-                    if ui.input(|i| i.key_pressed(egui::Key::ArrowRight)) {
-                        let _ = self.step_tx.send(StepDir::FORWARD);
+
+                    // flag the displayed word as difficult (not available while scrolling a text)
+                    if keys_enabled && ui.input(|i| i.key_pressed(egui::Key::M)) {
+                        if let Some(word) = self.displayed_word(poem_mode).map(|w| w.to_string()) {
+                            self.conf.toggle_difficult_word(&word);
+                        }
                     }
 
                     if ui.button(b_text).clicked() || space_pressed {
-                        self.running = !self.running;
+                        match self.conf.exercise {
+                            Exercise::Reading | Exercise::Ramp | Exercise::Peripheral => {
+                                self.running = !self.running
+                            }
+                            Exercise::Assessment => self.toggle_assessment(),
+                            Exercise::Reaction => self.toggle_reaction(),
+                            Exercise::Tachistoscope => self.toggle_tachistoscope(),
+                            Exercise::Flashcards => self.toggle_flashcards(),
+                            Exercise::Pictures => self.toggle_pictures(),
+                            Exercise::Spelling => self.toggle_spelling(),
+                        }
                     }
                 });
             });
@@ -267,12 +1757,69 @@ impl eframe::App for MyApp {
                 ui.add_space(10.0);
                 ui.add_enabled_ui(!poem_mode, |ui| {
                     ui.checkbox(&mut self.conf.random, "Random").on_hover_text("Zufällige Wörter im Wort/Buchstaben-Modus. Deaktiviert im Gedicht/Paragraph-Modus.");
-                })
+                });
+                ui.add_space(10.0);
+                ui.toggle_value(&mut self.show_difficult_words, "Schwierige Wörter")
+                    .on_hover_text("Mit M wird das aktuelle Wort als schwierig markiert.");
+                ui.add_space(10.0);
+                let sound = if self.conf.cues.muted { "🔇" } else { "🔊" };
+                if ui
+                    .selectable_label(!self.conf.cues.muted, sound)
+                    .on_hover_text("Töne bei jedem Wort, am Satzende und am Ende der Datei")
+                    .clicked()
+                {
+                    self.conf.cues.muted = !self.conf.cues.muted;
+                }
+                ui.add_space(10.0);
+                let mode = self.conf.mode;
+                ui.add_enabled_ui(self.conf.exercise == Exercise::Reading, |ui| {
+                    egui::ComboBox::from_id_salt("mode")
+                        .selected_text(self.conf.mode.label())
+                        .show_ui(ui, |ui| {
+                            for m in [TextMode::Auto, TextMode::Words, TextMode::Poem] {
+                                ui.selectable_value(&mut self.conf.mode, m, m.label());
+                            }
+                        });
+                });
+                if self.conf.mode != mode {
+                    self.conf.idx = 0;
+                    self.reload();
+                }
+                let exercise = self.conf.exercise;
+                egui::ComboBox::from_id_salt("exercise")
+                    .selected_text(self.conf.exercise.label())
+                    .show_ui(ui, |ui| {
+                        for e in Exercise::ALL {
+                            ui.selectable_value(&mut self.conf.exercise, e, e.label());
+                        }
+                    });
+                if self.conf.exercise != exercise {
+                    self.reload();
+                }
+                ui.toggle_value(&mut self.show_settings, "⚙")
+                    .on_hover_text("Einstellungen der Übungen");
+                ui.add_space(10.0);
+                if ui.button(format!("👤 {}", self.profiles.active)).clicked() {
+                    self.show_profiles = true;
+                }
+                ui.toggle_value(&mut self.show_stats, "Statistik");
+                ui.toggle_value(&mut self.show_library, "Bibliothek")
+                    .on_hover_text("Wortlisten und Texte aus einem Ordner");
+                ui.toggle_value(&mut self.show_file_info, "ℹ")
+                    .on_hover_text("Wortanzahl, Lesezeit und Lesbarkeit der Datei");
+                if ui
+                    .toggle_value(&mut self.show_schulte, "Schulte")
+                    .on_hover_text("Schulte-Tabelle zum Aufwärmen")
+                    .clicked()
+                {
+                    self.schulte = SchulteTable::new(&self.conf.schulte);
+                }
             });
         });
 
+        let rate = self.rate();
         if let Ok(mut write_guard) = self.rate_lock.write() {
-            *write_guard = self.conf.rate;
+            *write_guard = rate;
         }
         if let Ok(mut write_guard) = self.running_lock.write() {
             *write_guard = self.running;
@@ -280,17 +1827,24 @@ impl eframe::App for MyApp {
         if let Ok(mut write_guard) = self.random_lock.write() {
             *write_guard = self.conf.random;
         }
+        self.session.set_running(self.running);
+        self.ramp
+            .set_running(self.running && self.conf.exercise == Exercise::Ramp);
         self.conf.x = ctx.used_size().x;
         self.conf.y = ctx.used_size().y;
     }
 
     fn save(&mut self, _storage: &mut dyn Storage) {
-        let prefs_key = "config/gui";
-        match self.conf.save(&APP_INFO, prefs_key) {
-            Ok(_) => {}
-            Err(err) => {
-                println!("gui settings save failed: {:?}", err);
-            }
+        // a lesson only changes the settings while it runs
+        let mut conf = self.conf.clone();
+        if let Some(lesson) = &self.lesson {
+            lesson.restore(&mut conf);
         }
+        save_settings(&self.profiles.active, &self.overrides.persisted(&conf));
+        self.overrides.persisted_profiles(&self.profiles).store();
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.finish_session();
     }
 }
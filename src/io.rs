@@ -1,52 +1,760 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::vec::Vec;
 
+// inline markup of a word: `Haus|2x`, `*Baum*`, `{color:red}Maus` or a line `[pause 2s]`
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Markup {
+    // factor for how long the word is shown
+    pub duration: Option<f32>,
+    // seconds without a word
+    pub pause: Option<f32>,
+    pub emphasis: bool,
+    pub color: Option<[u8; 3]>,
+}
+
+fn parse_color(name: &str) -> Option<[u8; 3]> {
+    if let Some(hex) = name
+        .strip_prefix('#')
+        .filter(|h| h.len() == 6 && h.is_ascii())
+    {
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some([channel(0)?, channel(2)?, channel(4)?]);
+    }
+    match name.to_lowercase().as_str() {
+        "red" | "rot" => Some([220, 50, 47]),
+        "green" | "grün" => Some([0, 150, 0]),
+        "blue" | "blau" => Some([30, 100, 220]),
+        "yellow" | "gelb" => Some([230, 180, 0]),
+        "orange" => Some([240, 130, 0]),
+        "purple" | "lila" | "violett" => Some([150, 60, 200]),
+        "gray" | "grey" | "grau" => Some([128, 128, 128]),
+        _ => None,
+    }
+}
+
+// longer pauses and slower words are cut to these
+pub const MAX_PAUSE_S: f32 = 60.0;
+const MAX_DURATION: f32 = 10.0;
+
+// "2s", "500ms" or just "2" in seconds
+fn parse_seconds(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let seconds = if let Some(ms) = value.strip_suffix("ms") {
+        ms.trim().parse::<f32>().ok()? / 1000.0
+    } else {
+        value
+            .strip_suffix('s')
+            .unwrap_or(value)
+            .trim()
+            .parse()
+            .ok()?
+    };
+    Some(seconds)
+        .filter(|s| s.is_finite() && *s >= 0.0)
+        .map(|s| s.min(MAX_PAUSE_S))
+}
+
+impl Markup {
+    // the word without the markup, unknown markup stays part of the word
+    fn parse(token: &str) -> (String, Markup) {
+        let mut markup = Markup::default();
+        let mut text = token.trim().to_string();
+
+        if let Some(inner) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            if let Some(pause) = inner.trim().strip_prefix("pause").and_then(parse_seconds) {
+                markup.pause = Some(pause);
+                return (String::new(), markup);
+            }
+        }
+        if let Some(start) = text.find("{color:") {
+            if let Some(len) = text[start..].find('}') {
+                if let Some(color) = parse_color(text[start + 7..start + len].trim()) {
+                    markup.color = Some(color);
+                    text.replace_range(start..start + len + 1, "");
+                }
+            }
+        }
+        if let Some((word, factor)) = text.rsplit_once('|') {
+            if let Some(factor) = factor
+                .trim()
+                .strip_suffix('x')
+                .and_then(|f| f.parse::<f32>().ok())
+                .filter(|f| f.is_finite() && *f > 0.0)
+            {
+                markup.duration = Some(factor.min(MAX_DURATION));
+                text = word.to_string();
+            }
+        }
+        let trimmed = text.trim();
+        if trimmed.len() > 2 && trimmed.starts_with('*') && trimmed.ends_with('*') {
+            markup.emphasis = true;
+            text = trimmed[1..trimmed.len() - 1].to_string();
+        }
+        (text.trim().to_string(), markup)
+    }
+}
+
+// whitespace separated, but "[pause 2s]" stays one token
+fn tokens(line: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = vec![];
+    let mut open = false;
+    for part in line.split_whitespace() {
+        match tokens.last_mut() {
+            Some(last) if open => {
+                last.push(' ');
+                last.push_str(part);
+            }
+            _ => tokens.push(part.to_string()),
+        }
+        let last = tokens.last().map(|t| t.as_str()).unwrap_or_default();
+        open = last.starts_with('[') && !last.contains(']');
+    }
+    tokens
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Entry {
+    pub text: String,
+    // second column of a flashcard file, e.g. the translation
+    pub back: Option<String>,
+    pub image: Option<PathBuf>,
+    // recorded pronunciation
+    pub audio: Option<PathBuf>,
+    pub markup: Markup,
+}
+
+impl Entry {
+    pub fn new(text: &str) -> Entry {
+        Entry {
+            text: text.to_string(),
+            back: None,
+            image: None,
+            audio: None,
+            markup: Markup::default(),
+        }
+    }
+
+    // a word from a list, with its markup
+    fn parse(token: &str) -> Entry {
+        let (text, markup) = Markup::parse(token);
+        Entry {
+            markup,
+            ..Entry::new(&text)
+        }
+    }
+
+    fn from_columns(line: &str, separator: char) -> Entry {
+        let unquote = |s: &str| s.trim().trim_matches('"').trim().to_string();
+        match line.split_once(separator) {
+            Some((front, back)) => Entry {
+                back: Some(unquote(back)).filter(|b| !b.is_empty()),
+                ..Entry::parse(&unquote(front))
+            },
+            None => Entry::parse(&unquote(line)),
+        }
+    }
+
+    fn find_media(&mut self, folders: &MediaFolders) {
+        self.audio = self.named_file(folders, &AUDIO_EXTENSIONS);
+        // a second column naming an image, otherwise an image named like the word
+        if let Some(back) = self.back.as_ref().filter(|b| is_image(Path::new(b))) {
+            self.image = folders.find(back);
+            self.back = None;
+        } else {
+            self.image = self.named_file(folders, &IMAGE_EXTENSIONS);
+        }
+    }
+
+    fn named_file(&self, folders: &MediaFolders, extensions: &[&str]) -> Option<PathBuf> {
+        let word = self.text.trim();
+        if word.is_empty() {
+            return None;
+        }
+        [word.to_string(), word.to_lowercase()]
+            .iter()
+            .flat_map(|name| {
+                extensions
+                    .iter()
+                    .map(move |ext| format!("{}.{}", name, ext))
+            })
+            .find_map(|name| folders.find(&name))
+    }
+}
+
+// the images and recordings next to a list or in a folder named like it, listed once per load
+// so a long list does not look up every word on the disk
+struct MediaFolders {
+    folders: Vec<(PathBuf, HashSet<String>)>,
+}
+
+impl MediaFolders {
+    fn read(filename: &Path) -> MediaFolders {
+        // a bare name like "abc.txt" has an empty parent
+        let folder = filename
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        let folders = [
+            folder.join(filename.file_stem().unwrap_or_default()),
+            folder,
+        ]
+        .into_iter()
+        .map(|folder| {
+            let names = std::fs::read_dir(&folder)
+                .map(|dir| {
+                    dir.filter_map(|e| e.ok())
+                        .filter(|e| e.file_type().is_ok_and(|t| !t.is_dir()))
+                        .map(|e| e.file_name().to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            (folder, names)
+        })
+        .collect();
+        MediaFolders { folders }
+    }
+
+    // the first folder that has the file; a name with a folder in it is looked up directly
+    fn find(&self, name: &str) -> Option<PathBuf> {
+        if Path::new(name).components().count() > 1 {
+            return self
+                .folders
+                .iter()
+                .map(|(folder, _)| folder.join(name))
+                .find(|path| path.is_file());
+        }
+        self.folders
+            .iter()
+            .find(|(_, names)| names.contains(name))
+            .map(|(folder, _)| folder.join(name))
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub enum TextMode {
+    // the lower case names are for lesson files
+    #[default]
+    #[serde(alias = "auto")]
+    Auto,
+    #[serde(alias = "words")]
+    Words,
+    #[serde(alias = "poem")]
+    Poem,
+}
+
+impl TextMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextMode::Auto => "Automatisch",
+            TextMode::Words => "Wörter/Buchstaben",
+            TextMode::Poem => "Gedicht/Paragraph",
+        }
+    }
+}
+
+const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+const AUDIO_EXTENSIONS: [&str; 2] = ["ogg", "wav"];
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.as_str()))
+}
+
+// a line in quotes is one entry, e.g. "guten Morgen" in an exported list
+fn unquote_line(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix('"')?
+        .strip_suffix('"')
+        .filter(|inner| !inner.contains('"'))
+}
+
 fn is_word_per_line(lines: &[String]) -> bool {
-    // true when every non-empty line contains at most one whitespace-separated token
+    // true when every non-empty line contains at most one whitespace-separated token or is quoted
     lines
         .iter()
         .filter(|l| !l.trim().is_empty())
-        .all(|l| l.trim().split_whitespace().count() <= 1)
-}
-
-pub fn read_words_from_file(filename: &PathBuf) -> Option<(Vec<String>, bool)> {
-    match File::open(filename) {
-        Ok(file) => {
-            let reader = BufReader::new(file);
-            let mut lines = vec![];
-            for line in reader.lines() {
-                let line = line.unwrap();
-                if line.contains("\r\n") {
-                    for line in line.split("\r\n") {
-                        lines.push(line.to_owned());
-                    }
-                } else if line.contains("\n") {
-                    for line in line.split("\n") {
-                        lines.push(line.to_owned());
-                    }
-                } else if line.contains("\r") {
-                    for line in line.split("\r") {
-                        lines.push(line.to_owned());
-                    }
-                } else {
-                    lines.push(line);
-                }
+        .all(|l| unquote_line(l).is_some() || tokens(l).len() <= 1)
+}
+
+// exactly two columns with a word in front, so indented lines and tables stay text
+fn is_two_columns(line: &str, separator: char) -> bool {
+    match line.split_once(separator) {
+        Some((front, back)) => !front.trim().is_empty() && !back.contains(separator),
+        None => false,
+    }
+}
+
+// a picture card like `Apfel;apfel.png` whose picture exists
+fn names_image(line: &str, folders: &MediaFolders) -> bool {
+    is_two_columns(line, ';')
+        && line.split_once(';').is_some_and(|(_, back)| {
+            let back = back.trim().trim_matches('"').trim();
+            is_image(Path::new(back)) && folders.find(back).is_some()
+        })
+}
+
+fn column_separator(filename: &Path, lines: &[String], folders: &MediaFolders) -> Option<char> {
+    // two-column files: .tsv/.csv or a plain text file with two tab-separated columns on every line;
+    // a semicolon only for picture cards, texts use it as punctuation
+    let extension = filename
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let non_empty: Vec<&String> = lines.iter().filter(|l| !l.trim().is_empty()).collect();
+    if non_empty.is_empty() {
+        return None;
+    }
+    if extension == "tsv" || non_empty.iter().all(|l| is_two_columns(l, '\t')) {
+        Some('\t')
+    } else if extension != "csv" && non_empty.iter().all(|l| names_image(l, folders)) {
+        Some(';')
+    } else if extension == "csv" {
+        if lines.iter().any(|l| l.contains(';')) {
+            Some(';')
+        } else {
+            Some(',')
+        }
+    } else {
+        None
+    }
+}
+
+// settings at the top of a text file, between two lines with "---"
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct FrontMatter {
+    pub rate: Option<f32>,
+    pub mode: Option<TextMode>,
+    pub random: Option<bool>,
+    pub font_size: Option<f32>,
+}
+
+fn number_in(value: &str, min: f32, max: f32) -> Option<f32> {
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|v| (min..=max).contains(v))
+}
+
+// the header and the number of lines it takes, None if the file does not start with one
+fn parse_front_matter(lines: &[String]) -> Option<(FrontMatter, usize)> {
+    if lines.first()?.trim_start_matches('\u{feff}').trim() != "---" {
+        return None;
+    }
+    let end = lines.iter().skip(1).position(|l| l.trim() == "---")? + 1;
+    let mut header = FrontMatter::default();
+    for line in &lines[1..end] {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key.trim().to_lowercase(), value.trim().to_lowercase()),
+            None => {
+                println!("ignoring header line `{}`", line);
+                continue;
+            }
+        };
+        let valid = match key.as_str() {
+            "rate" => {
+                header.rate = number_in(&value, 10.0, 800.0);
+                header.rate.is_some()
+            }
+            "font_size" => {
+                header.font_size = number_in(&value, 40.0, 200.0);
+                header.font_size.is_some()
+            }
+            "random" => {
+                header.random = match value.as_str() {
+                    "true" | "yes" | "ja" => Some(true),
+                    "false" | "no" | "nein" => Some(false),
+                    _ => None,
+                };
+                header.random.is_some()
+            }
+            "mode" => {
+                header.mode = match value.as_str() {
+                    "auto" => Some(TextMode::Auto),
+                    "words" | "letters" | "wörter" | "buchstaben" => Some(TextMode::Words),
+                    "poem" | "paragraph" | "gedicht" | "text" => Some(TextMode::Poem),
+                    _ => None,
+                };
+                header.mode.is_some()
+            }
+            _ => false,
+        };
+        if !valid {
+            println!("ignoring header line `{}`", line);
+        }
+    }
+    Some((header, end + 1))
+}
+
+fn read_lines(filename: &Path) -> std::io::Result<Vec<String>> {
+    let reader = BufReader::new(File::open(filename)?);
+    let mut lines: Vec<String> = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        if line.contains("\r\n") {
+            for line in line.split("\r\n") {
+                lines.push(line.to_owned());
+            }
+        } else if line.contains("\n") {
+            for line in line.split("\n") {
+                lines.push(line.to_owned());
             }
-            if !is_word_per_line(&lines) {
+        } else if line.contains("\r") {
+            for line in line.split("\r") {
+                lines.push(line.to_owned());
+            }
+        } else {
+            lines.push(line);
+        }
+    }
+    Ok(lines)
+}
+
+pub fn read_front_matter(filename: &Path) -> FrontMatter {
+    read_lines(filename)
+        .ok()
+        .and_then(|lines| parse_front_matter(&lines))
+        .map(|(header, _)| header)
+        .unwrap_or_default()
+}
+
+pub fn read_words_from_file(filename: &Path) -> Option<(Vec<Entry>, bool)> {
+    match read_lines(filename) {
+        Ok(mut lines) => {
+            // the header is applied by whoever opens the file, it is not read
+            if let Some((_, len)) = parse_front_matter(&lines) {
+                lines.drain(..len);
+            }
+            let folders = MediaFolders::read(filename);
+            if let Some(separator) = column_separator(filename, &lines, &folders) {
+                let mut entries: Vec<Entry> = lines
+                    .iter()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(|l| Entry::from_columns(l, separator))
+                    .collect();
+                entries.iter_mut().for_each(|e| e.find_media(&folders));
+                Some((entries, false))
+            } else if !is_word_per_line(&lines) {
                 // concatenate all lines with "   " as separator
-                lines = vec![lines
+                let text = lines
                     .iter()
                     .filter(|l| !l.trim().is_empty())
                     .cloned()
                     .collect::<Vec<String>>()
-                    .join("   ")];
-                Some((lines, true))
+                    .join("   ");
+                Some((vec![Entry::new(&text)], true))
             } else {
-                Some((lines, false))
+                let mut entries: Vec<Entry> = lines
+                    .iter()
+                    .map(|l| Entry::parse(unquote_line(l).unwrap_or(l)))
+                    .collect();
+                entries.iter_mut().for_each(|e| e.find_media(&folders));
+                Some((entries, false))
             }
         }
         Err(_) => None,
     }
 }
+
+// a text that scrolls as a whole shows the plain words, pauses are left out
+fn without_markup(text: &str) -> String {
+    text.split("   ")
+        .map(|line| {
+            tokens(line)
+                .iter()
+                .map(|t| Markup::parse(t).0)
+                .filter(|w| !w.is_empty())
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<String>>()
+        .join("   ")
+}
+
+pub fn apply_mode(words: Vec<Entry>, poem_mode: bool, mode: TextMode) -> (Vec<Entry>, bool) {
+    // overrides the detected mode, e.g. to read a paragraph word by word
+    match mode {
+        TextMode::Words if poem_mode => (
+            words
+                .iter()
+                .flat_map(|e| tokens(&e.text))
+                .map(|t| Entry::parse(&t))
+                .collect(),
+            false,
+        ),
+        TextMode::Poem if !poem_mode => (
+            vec![Entry::new(
+                &words
+                    .iter()
+                    .filter(|e| !e.text.trim().is_empty())
+                    .map(|e| e.text.clone())
+                    .collect::<Vec<String>>()
+                    .join("   "),
+            )],
+            true,
+        ),
+        // the text keeps its markup up to here, so it can still be read word by word
+        _ if poem_mode => (
+            words
+                .iter()
+                .map(|e| Entry::new(&without_markup(&e.text)))
+                .collect(),
+            true,
+        ),
+        _ => (words, poem_mode),
+    }
+}
+
+// the occurrence of the word closest to the position, to keep the place in a changed file
+pub fn find_word(words: &[Entry], word: &str, near: usize) -> Option<usize> {
+    // pauses have no text, one would match any other
+    if word.is_empty() {
+        return None;
+    }
+    words
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| e.text == word)
+        .map(|(i, _)| i)
+        .min_by_key(|i| i.abs_diff(near))
+}
+
+pub fn write_words_to_file(filename: &PathBuf, words: &[String]) -> std::io::Result<()> {
+    // one word per line, so the file is read back in word-per-line mode;
+    // several words like a flashcard front are quoted to stay one entry
+    let mut writer = BufWriter::new(File::create(filename)?);
+    for word in words {
+        if word.contains(char::is_whitespace) {
+            writeln!(writer, "\"{}\"", word)?;
+        } else {
+            writeln!(writer, "{}", word)?;
+        }
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(token: &str) -> (String, Markup) {
+        Markup::parse(token)
+    }
+
+    #[test]
+    fn plain_word_has_no_markup() {
+        assert_eq!(parse(" Haus "), ("Haus".to_string(), Markup::default()));
+    }
+
+    #[test]
+    fn parses_duration() {
+        assert_eq!(parse("Haus|2x").1.duration, Some(2.0));
+        assert_eq!(parse("Haus|0.5x").1.duration, Some(0.5));
+        assert_eq!(parse("Haus|2x").0, "Haus");
+        // only the last bar is markup
+        assert_eq!(
+            parse("ein|zwei|3x"),
+            (
+                "ein|zwei".to_string(),
+                Markup {
+                    duration: Some(3.0),
+                    ..Markup::default()
+                }
+            )
+        );
+        // capped to the longest duration
+        assert_eq!(parse("Haus|50x").1.duration, Some(MAX_DURATION));
+    }
+
+    #[test]
+    fn invalid_duration_stays_part_of_the_word() {
+        for token in [
+            "Haus|infx",
+            "Haus|NaNx",
+            "Haus|0x",
+            "Haus|-1x",
+            "Haus|x",
+            "Haus|2",
+        ] {
+            assert_eq!(
+                parse(token),
+                (token.to_string(), Markup::default()),
+                "{}",
+                token
+            );
+        }
+        assert_eq!(parse("Haus|1e30x").1.duration, Some(MAX_DURATION));
+    }
+
+    #[test]
+    fn parses_emphasis() {
+        assert_eq!(
+            parse("*Baum*"),
+            (
+                "Baum".to_string(),
+                Markup {
+                    emphasis: true,
+                    ..Markup::default()
+                }
+            )
+        );
+        // a single star or two stars alone are text
+        assert_eq!(parse("*Baum").1, Markup::default());
+        assert_eq!(parse("**"), ("**".to_string(), Markup::default()));
+    }
+
+    #[test]
+    fn parses_color() {
+        assert_eq!(parse("{color:red}Maus").1.color, Some([220, 50, 47]));
+        assert_eq!(parse("{color:grün}Maus").1.color, Some([0, 150, 0]));
+        assert_eq!(
+            parse("{color:#102030}Maus"),
+            (
+                "Maus".to_string(),
+                Markup {
+                    color: Some([16, 32, 48]),
+                    ..Markup::default()
+                }
+            )
+        );
+        // unknown colours and broken markup stay part of the word
+        assert_eq!(
+            parse("{color:pink}Maus"),
+            ("{color:pink}Maus".to_string(), Markup::default())
+        );
+        assert_eq!(parse("{color:#1020}Maus").1.color, None);
+        assert_eq!(parse("{color:redMaus").1.color, None);
+        // only the first colour is taken out
+        assert_eq!(parse("{color:red}{color:blue}Maus").0, "{color:blue}Maus");
+    }
+
+    #[test]
+    fn combines_markup() {
+        let (text, markup) = parse("{color:blau}*Haus*|2x");
+        assert_eq!(text, "Haus");
+        assert_eq!(
+            markup,
+            Markup {
+                duration: Some(2.0),
+                pause: None,
+                emphasis: true,
+                color: Some([30, 100, 220]),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_pause() {
+        assert_eq!(
+            parse("[pause 2s]"),
+            (
+                String::new(),
+                Markup {
+                    pause: Some(2.0),
+                    ..Markup::default()
+                }
+            )
+        );
+        assert_eq!(parse("[pause 500ms]").1.pause, Some(0.5));
+        assert_eq!(parse("[pause 3]").1.pause, Some(3.0));
+        assert_eq!(parse("[pause 1.5 s]").1.pause, Some(1.5));
+        // capped to a minute
+        assert_eq!(parse("[pause 600s]").1.pause, Some(MAX_PAUSE_S));
+        assert_eq!(parse("[pause 1e30s]").1.pause, Some(MAX_PAUSE_S));
+    }
+
+    #[test]
+    fn invalid_pause_stays_a_word() {
+        for token in [
+            "[pause infs]",
+            "[pause NaN]",
+            "[pause -1s]",
+            "[pause]",
+            "[pause zwei]",
+            "[pause 10m0s]",
+            "[Pause 2s]",
+            "[halt 2s]",
+        ] {
+            assert_eq!(
+                parse(token),
+                (token.to_string(), Markup::default()),
+                "{}",
+                token
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_pause_as_one_token() {
+        assert_eq!(tokens("Er [pause 2s] kam"), ["Er", "[pause 2s]", "kam"]);
+        assert_eq!(tokens("[pause 500 ms]"), ["[pause 500 ms]"]);
+        // an unclosed bracket takes the rest of the line
+        assert_eq!(tokens("[pause 2s kam"), ["[pause 2s kam"]);
+        assert_eq!(tokens("  *Baum*   Haus|2x "), ["*Baum*", "Haus|2x"]);
+        assert!(tokens("   ").is_empty());
+    }
+
+    #[test]
+    fn scrolling_text_has_no_markup() {
+        let text = "Der *Baum* {color:red}steht|2x   [pause 2s]   im Wald";
+        assert_eq!(without_markup(text), "Der Baum steht   im Wald");
+    }
+
+    #[test]
+    fn exported_words_are_read_back_as_a_list() {
+        let path =
+            std::env::temp_dir().join(format!("word-nerd-{}-export.txt", std::process::id()));
+        let words = ["Haus".to_string(), "guten Morgen".to_string()];
+        write_words_to_file(&path, &words).unwrap();
+        let (entries, poem_mode) = read_words_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!poem_mode);
+        assert_eq!(
+            entries
+                .iter()
+                .map(|e| e.text.as_str())
+                .collect::<Vec<&str>>(),
+            ["Haus", "guten Morgen"]
+        );
+    }
+
+    #[test]
+    fn finds_pictures_next_to_a_relative_list() {
+        // relative to the working directory, like `word-nerd analyze liste.txt`
+        let name = format!("word-nerd-{}-bilder", std::process::id());
+        let list = PathBuf::from(format!("{}.txt", name));
+        let picture = format!("{}.png", name);
+        std::fs::write(&list, format!("Apfel;{}\nBirne;{}\n", picture, picture)).unwrap();
+        std::fs::write(&picture, b"").unwrap();
+        let words = read_words_from_file(&list);
+        std::fs::remove_file(&list).unwrap();
+        std::fs::remove_file(&picture).unwrap();
+        let (entries, poem_mode) = words.unwrap();
+        assert!(!poem_mode);
+        assert_eq!(entries[0].text, "Apfel");
+        assert_eq!(entries[0].back, None);
+        assert_eq!(entries[0].image, Some(PathBuf::from(".").join(&picture)));
+    }
+
+    #[test]
+    fn pause_matches_no_word() {
+        let words = [
+            Entry::parse("Haus"),
+            Entry::parse("[pause 2s]"),
+            Entry::parse("Baum"),
+            Entry::parse("[pause 1s]"),
+        ];
+        assert_eq!(find_word(&words, "", 1), None);
+        assert_eq!(find_word(&words, "Baum", 0), Some(2));
+    }
+}
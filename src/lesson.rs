@@ -0,0 +1,262 @@
+use crate::gui::SettingsContainer;
+use crate::io::{FrontMatter, TextMode};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+pub struct LessonItem {
+    // relative to the lesson file
+    pub file: PathBuf,
+    // the header of the file or automatic when missing
+    pub mode: Option<TextMode>,
+    // the header of the file or the current speed when missing
+    pub rate: Option<f32>,
+    #[serde(default = "default_repetitions")]
+    pub repetitions: u32,
+    // the break after this item, the lesson's break when missing
+    pub pause_s: Option<f32>,
+}
+
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+pub struct Lesson {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default = "default_pause_s")]
+    pub pause_s: f32,
+    pub items: Vec<LessonItem>,
+}
+
+fn default_repetitions() -> u32 {
+    1
+}
+
+fn default_pause_s() -> f32 {
+    30.0
+}
+
+// an hour at most, longer breaks are a new lesson
+const MAX_PAUSE_S: f32 = 3600.0;
+
+fn check_pause(pause_s: f32) -> Result<(), String> {
+    if (0.0..=MAX_PAUSE_S).contains(&pause_s) {
+        Ok(())
+    } else {
+        Err(format!(
+            "the break of {} s is not between 0 and {} s",
+            pause_s, MAX_PAUSE_S
+        ))
+    }
+}
+
+pub fn is_lesson(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| e == "toml" || e == "json")
+}
+
+impl Lesson {
+    pub fn load(path: &Path) -> Result<Lesson, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let is_json = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+        let mut lesson: Lesson = if is_json {
+            serde_json::from_str(&text).map_err(|err| err.to_string())?
+        } else {
+            toml::from_str(&text).map_err(|err| err.to_string())?
+        };
+        if lesson.items.is_empty() {
+            return Err("the lesson has no items".to_string());
+        }
+        check_pause(lesson.pause_s)?;
+        for pause_s in lesson.items.iter().filter_map(|item| item.pause_s) {
+            check_pause(pause_s)?;
+        }
+        let folder = path.parent().unwrap_or(Path::new(""));
+        for item in lesson.items.iter_mut() {
+            item.file = folder.join(&item.file);
+        }
+        if lesson.title.is_empty() {
+            lesson.title = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+        }
+        Ok(lesson)
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum LessonState {
+    Reading,
+    Break { until: Instant },
+    Done,
+}
+
+// what the learner had open before, restored when the lesson ends
+struct Previous {
+    file_path: PathBuf,
+    idx: isize,
+    mode: TextMode,
+    rate: f32,
+    random: bool,
+    font_size: f32,
+}
+
+pub struct LessonRun {
+    pub lesson: Lesson,
+    pub item: usize,
+    pub repetition: u32,
+    pub state: LessonState,
+    previous: Previous,
+}
+
+impl LessonRun {
+    pub fn new(lesson: Lesson, conf: &SettingsContainer) -> LessonRun {
+        LessonRun {
+            lesson,
+            item: 0,
+            repetition: 0,
+            state: LessonState::Reading,
+            previous: Previous {
+                file_path: conf.file_path.clone(),
+                idx: conf.idx,
+                mode: conf.mode,
+                rate: conf.rate,
+                random: conf.random,
+                font_size: conf.font_size,
+            },
+        }
+    }
+
+    pub fn current(&self) -> &LessonItem {
+        &self.lesson.items[self.item]
+    }
+
+    // the settings for the current item, the file is read from the start and in order;
+    // the lesson wins over the header of the file
+    pub fn apply(&self, conf: &mut SettingsContainer, header: &FrontMatter) {
+        let item = self.current();
+        conf.file_path = item.file.clone();
+        conf.idx = 0;
+        conf.mode = item.mode.or(header.mode).unwrap_or_default();
+        if let Some(rate) = item.rate.or(header.rate) {
+            conf.rate = rate.clamp(10.0, 800.0);
+        }
+        if let Some(font_size) = header.font_size {
+            conf.font_size = font_size;
+        }
+        conf.random = false;
+    }
+
+    pub fn restore(&self, conf: &mut SettingsContainer) {
+        conf.file_path = self.previous.file_path.clone();
+        conf.idx = self.previous.idx;
+        conf.mode = self.previous.mode;
+        conf.rate = self.previous.rate;
+        conf.random = self.previous.random;
+        conf.font_size = self.previous.font_size;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state != LessonState::Reading
+    }
+
+    // called when the file starts over
+    pub fn file_finished(&mut self, now: Instant) {
+        if self.state != LessonState::Reading {
+            return;
+        }
+        self.repetition += 1;
+        if self.repetition < self.current().repetitions.max(1) {
+            return;
+        }
+        self.state = if self.item + 1 < self.lesson.items.len() {
+            let pause_s = self.current().pause_s.unwrap_or(self.lesson.pause_s);
+            LessonState::Break {
+                until: now + Duration::from_secs_f32(pause_s),
+            }
+        } else {
+            LessonState::Done
+        };
+    }
+
+    pub fn remaining_break(&self, now: Instant) -> Option<Duration> {
+        match self.state {
+            LessonState::Break { until } => Some(until.saturating_duration_since(now)),
+            _ => None,
+        }
+    }
+
+    // returns true when the break is over and the next item has to be loaded
+    pub fn update(&mut self, now: Instant) -> bool {
+        match self.state {
+            LessonState::Break { until } if now >= until => {
+                self.next_item();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn next_item(&mut self) {
+        self.item = (self.item + 1).min(self.lesson.items.len() - 1);
+        self.repetition = 0;
+        self.state = LessonState::Reading;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::scroll_text;
+
+    const POEM_LESSON: &str = r#"
+        pause_s = 20
+
+        [[items]]
+        file = "manimatter.txt"
+        mode = "poem"
+        rate = 400
+        repetitions = 2
+
+        [[items]]
+        file = "abc.txt"
+    "#;
+
+    #[test]
+    fn poem_item_finishes_after_scrolling_through() {
+        let lesson: Lesson = toml::from_str(POEM_LESSON).unwrap();
+        let mut run = LessonRun::new(lesson, &SettingsContainer::default());
+        let now = Instant::now();
+        // a text 2000 px long including the gap, 60 frames per second at 400 px/s
+        let (mut offset, cycle, step) = (0.0, 2000.0, 400.0 / 60.0);
+        let mut frames = 0;
+        while run.state == LessonState::Reading && frames < 10_000 {
+            for _ in 0..scroll_text(&mut offset, step, cycle) {
+                run.file_finished(now);
+            }
+            frames += 1;
+        }
+        // two passes of five seconds each, not a few engine ticks
+        assert!((599..=601).contains(&frames), "{} frames", frames);
+        assert_eq!(run.remaining_break(now), Some(Duration::from_secs(20)));
+    }
+
+    #[test]
+    fn rejects_endless_breaks() {
+        for pause in ["inf", "nan", "-1", "1e30"] {
+            let text = format!("pause_s = {}\n[[items]]\nfile = \"abc.txt\"\n", pause);
+            let path = std::env::temp_dir().join(format!(
+                "word-nerd-{}-lesson-{}.toml",
+                std::process::id(),
+                pause
+            ));
+            std::fs::write(&path, text).unwrap();
+            let lesson = Lesson::load(&path);
+            std::fs::remove_file(&path).unwrap();
+            assert!(lesson.is_err(), "{}", pause);
+        }
+    }
+}
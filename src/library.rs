@@ -0,0 +1,154 @@
+use crate::io::{apply_mode, read_words_from_file, TextMode};
+use eframe::egui;
+use eframe::egui::RichText;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const MAX_RECENT: usize = 10;
+const TEXT_EXTENSIONS: [&str; 3] = ["txt", "csv", "tsv"];
+
+// the newest file first, without duplicates
+pub fn add_recent(recent: &mut Vec<PathBuf>, path: &Path) {
+    recent.retain(|p| p != path);
+    recent.insert(0, path.to_path_buf());
+    recent.truncate(MAX_RECENT);
+}
+
+pub struct LibraryEntry {
+    pub path: PathBuf,
+    pub title: String,
+    pub words: usize,
+    // number of words or lines the reader steps through
+    pub entries: usize,
+    pub poem_mode: bool,
+}
+
+pub struct Library {
+    pub folder: PathBuf,
+    pub entries: Vec<LibraryEntry>,
+}
+
+impl Library {
+    // only the folder itself, the picture and recording folders next to the lists are skipped
+    pub fn scan(folder: &Path) -> Library {
+        let mut entries = vec![];
+        match std::fs::read_dir(folder) {
+            Ok(dir) => {
+                for path in dir.filter_map(|e| e.ok()).map(|e| e.path()) {
+                    let is_text = path
+                        .extension()
+                        .map(|e| e.to_string_lossy().to_lowercase())
+                        .is_some_and(|e| TEXT_EXTENSIONS.contains(&e.as_str()));
+                    if !is_text {
+                        continue;
+                    }
+                    if let Some((words, poem_mode)) = read_words_from_file(&path)
+                        .map(|(words, poem_mode)| apply_mode(words, poem_mode, TextMode::Auto))
+                    {
+                        entries.push(LibraryEntry {
+                            title: path
+                                .file_stem()
+                                .map(|s| s.to_string_lossy().replace('_', " "))
+                                .unwrap_or_default(),
+                            words: words
+                                .iter()
+                                .map(|w| w.text.split_whitespace().count())
+                                .sum(),
+                            entries: words.len(),
+                            poem_mode,
+                            path,
+                        });
+                    }
+                }
+            }
+            Err(err) => println!("could not read folder {}: {:?}", folder.display(), err),
+        }
+        entries.sort_by_key(|e| e.title.to_lowercase());
+        Library {
+            folder: folder.to_path_buf(),
+            entries,
+        }
+    }
+}
+
+// returns the file to open
+pub fn library_panel(
+    ctx: &egui::Context,
+    library: &mut Option<Library>,
+    folder: &mut Option<PathBuf>,
+    positions: &BTreeMap<String, usize>,
+    current: &Path,
+) -> Option<PathBuf> {
+    let mut selected = None;
+    egui::SidePanel::left("library")
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.add_space(10.0);
+            ui.label(RichText::new("Bibliothek").size(20.0).strong());
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                if ui.button("Ordner wählen").clicked() {
+                    if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                        *folder = Some(path);
+                    }
+                }
+                if ui
+                    .add_enabled(folder.is_some(), egui::Button::new("⟳"))
+                    .on_hover_text("Ordner neu einlesen")
+                    .clicked()
+                {
+                    *library = None;
+                }
+            });
+            let Some(folder) = folder else {
+                ui.label("Noch kein Ordner gewählt.");
+                return;
+            };
+            // another profile may have another folder
+            if library.as_ref().is_some_and(|l| &l.folder != folder) {
+                *library = None;
+            }
+            // scanned once when the panel is opened, the lists are read completely
+            let library = library.get_or_insert_with(|| Library::scan(folder));
+            ui.label(folder.display().to_string());
+            ui.add_space(10.0);
+
+            if library.entries.is_empty() {
+                ui.label("Keine Wortlisten oder Texte gefunden.");
+                return;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("library_entries")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Titel");
+                        ui.strong("Wörter");
+                        ui.strong("Modus");
+                        ui.strong("Gelesen bis");
+                        ui.end_row();
+                        for entry in &library.entries {
+                            if ui
+                                .selectable_label(entry.path == current, &entry.title)
+                                .on_hover_text(entry.path.display().to_string())
+                                .clicked()
+                            {
+                                selected = Some(entry.path.clone());
+                            }
+                            ui.label(entry.words.to_string());
+                            ui.label(if entry.poem_mode { "Text" } else { "Wörter" });
+                            match positions.get(&entry.path.display().to_string()) {
+                                Some(position) => ui.label(format!(
+                                    "{} / {}",
+                                    (*position).min(entry.entries.saturating_sub(1)) + 1,
+                                    entry.entries
+                                )),
+                                None => ui.label("–"),
+                            };
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+    selected
+}
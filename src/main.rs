@@ -1,29 +1,53 @@
+mod analysis;
+mod assessment;
+mod audio;
+mod cli;
+mod cues;
+mod flashcards;
 mod gui;
 mod io;
+mod lesson;
+mod library;
+mod peripheral;
+mod pictures;
+mod profiles;
+mod ramp;
+mod reaction;
+mod schulte;
+mod speech;
+mod spelling;
+mod stats;
+mod tachistoscope;
+mod tui;
+mod watch;
 
-use crate::gui::{MyApp, SettingsContainer, StepDir};
+use crate::cli::{Cli, Command, Overrides};
+use crate::gui::{LoadRequest, MyApp, Progress, StepDir};
+use crate::profiles::{load_settings, Profiles};
+use clap::Parser;
 use eframe::egui::ViewportBuilder;
 use eframe::epaint::text::{FontInsert, FontPriority, InsertFontFamily};
 use eframe::{egui, icon_data};
-use io::read_words_from_file;
-use preferences::{AppInfo, Preferences};
+use io::{apply_mode, find_word, read_words_from_file, Entry, TextMode, MAX_PAUSE_S};
+use preferences::AppInfo;
 use rand::Rng;
 use std::path::PathBuf;
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{mpsc, Arc, RwLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 const APP_INFO: AppInfo = AppInfo {
     name: "Word Nerd",
     author: "Linus Leo Stöckli, Lea Höfliger",
 };
 const HISTORY_SIZE: usize = 512;
+const FONT: &[u8] = include_bytes!("../fonts/DCH-Basisschrift.ttf");
 
 fn add_font(ctx: &egui::Context) {
     ctx.add_font(FontInsert::new(
         "my_font",
-        egui::FontData::from_static(include_bytes!("../fonts/DCH-Basisschrift.ttf")),
+        egui::FontData::from_static(FONT),
         vec![InsertFontFamily {
             family: egui::FontFamily::Name("my_font".into()),
             priority: FontPriority::Highest,
@@ -31,14 +55,27 @@ fn add_font(ctx: &egui::Context) {
     ));
 }
 
+// in short steps, so Stop does not have to wait for a long word or pause
+fn sleep_while_running(duration: Duration, running_lock: &RwLock<bool>) {
+    let until = Instant::now() + duration;
+    loop {
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() || !running_lock.read().map(|r| *r).unwrap_or(false) {
+            return;
+        }
+        std::thread::sleep(left.min(Duration::from_millis(50)));
+    }
+}
+
 fn main_thread(
     rate_lock: Arc<RwLock<f32>>,
     random_lock: Arc<RwLock<bool>>,
     running_lock: Arc<RwLock<bool>>,
     mode_lock: Arc<RwLock<bool>>,
-    word_lock: Arc<RwLock<String>>,
+    word_lock: Arc<RwLock<Entry>>,
+    progress_lock: Arc<RwLock<Progress>>,
     step_rx: Receiver<StepDir>,
-    load_rx: Receiver<PathBuf>,
+    load_rx: Receiver<LoadRequest>,
     sync_tx: Sender<bool>,
 ) {
     // reads data from mutex, samples and saves if needed
@@ -47,10 +84,15 @@ fn main_thread(
     let mut randomizer = false;
     let mut idx = 0;
     let mut i = 0;
+    let mut wraps = 0;
+    // the first word of a new file is not the file starting over
+    let mut loaded = false;
+    // words shown in random order since the file was last read through
+    let mut random_shown = 0;
     let mut word;
     let file_path = PathBuf::from("abc.txt");
-    let mut words = vec!["keine gültige Datei gefunden".to_string()];
-    match read_words_from_file(&file_path) {
+    let mut words = vec![Entry::new("keine gültige Datei gefunden")];
+    match read_words_from_file(&file_path).map(|(w, mode)| apply_mode(w, mode, TextMode::Auto)) {
         None => {}
         Some((w, mode)) => {
             words = w;
@@ -74,13 +116,47 @@ fn main_thread(
         }
 
         match load_rx.recv_timeout(Duration::from_millis(1)) {
-            Ok(fp) => {
+            Ok(request) => {
                 // load file
-                if let Some((w, mode)) = read_words_from_file(&fp) {
+                if let Some((w, mode)) = read_words_from_file(&request.path)
+                    .map(|(w, mode)| apply_mode(w, mode, request.mode))
+                    .filter(|(w, _)| !w.is_empty())
+                {
                     words = w;
                     if let Ok(mut write_guard) = mode_lock.write() {
                         *write_guard = mode;
                     }
+                    let matched = request
+                        .word
+                        .as_ref()
+                        .and_then(|word| find_word(&words, word, request.position));
+                    let position = match matched {
+                        // the file changed while reading, the word stays and the next step continues after it
+                        Some(position) => {
+                            idx = position;
+                            loaded = false;
+                            position
+                        }
+                        // continue where the learner stopped, the next step shows the stored word
+                        None => {
+                            let position = request.position.min(words.len() - 1);
+                            idx = (position + words.len() - 1) % words.len();
+                            loaded = true;
+                            position
+                        }
+                    };
+                    history = vec![idx];
+                    random_shown = 0;
+                    i = 0;
+                    if let Some(position) = matched {
+                        word = words[position].clone();
+                        if let Ok(mut write_guard) = word_lock.write() {
+                            *write_guard = word.clone();
+                        }
+                    }
+                    if let Ok(mut write_guard) = progress_lock.write() {
+                        write_guard.position = position;
+                    }
                 }
             }
             Err(..) => (),
@@ -93,18 +169,40 @@ fn main_thread(
                 if *history.last().unwrap() == idx {
                     idx = (idx + 1) % words.len();
                 }
+                // in random order the file is read through after as many words as it has
+                random_shown += 1;
+                if random_shown >= words.len() && words.len() > 1 {
+                    random_shown = 0;
+                    wraps += 1;
+                }
             } else {
                 idx = (idx + 1) % words.len();
+                // a text that scrolls as a whole is one entry, the window reports its end
+                if idx == 0 && !loaded && words.len() > 1 {
+                    wraps += 1;
+                }
             }
 
             word = words[idx].clone();
+            loaded = false;
             history.push(idx);
             i = history.len() - 1;
 
             if let Ok(mut write_guard) = word_lock.write() {
                 *write_guard = word.clone();
             }
-            std::thread::sleep(Duration::from_millis((60.0 / rate * 1000.0) as u64));
+            if let Ok(mut write_guard) = progress_lock.write() {
+                write_guard.position = idx;
+                write_guard.words_shown += 1;
+                write_guard.wraps = wraps;
+            }
+            // the markup can make a word stay longer or shorter, a pause has its own length
+            let seconds = match word.markup.pause {
+                Some(pause) => pause,
+                None => 60.0 / rate * word.markup.duration.unwrap_or(1.0),
+            };
+            let seconds = Duration::try_from_secs_f32(seconds.min(MAX_PAUSE_S)).unwrap_or_default();
+            sleep_while_running(seconds, &running_lock);
             sync_tx.send(true).expect("sync_tx send failed");
         } else {
             match step_rx.recv_timeout(Duration::from_millis(1)) {
@@ -121,8 +219,16 @@ fn main_thread(
                                     if *history.last().unwrap() == idx {
                                         idx = (idx + 1) % words.len();
                                     }
+                                    random_shown += 1;
+                                    if random_shown >= words.len() && words.len() > 1 {
+                                        random_shown = 0;
+                                        wraps += 1;
+                                    }
                                 } else {
                                     idx = (idx + 1) % words.len();
+                                    if idx == 0 && !loaded && words.len() > 1 {
+                                        wraps += 1;
+                                    }
                                 }
                                 history.push(idx);
                                 i = history.len() - 1;
@@ -144,9 +250,15 @@ fn main_thread(
                         }
                     }
                     word = words[idx].clone();
+                    loaded = false;
                     if let Ok(mut write_guard) = word_lock.write() {
                         *write_guard = word.clone();
                     }
+                    if let Ok(mut write_guard) = progress_lock.write() {
+                        write_guard.position = idx;
+                        write_guard.words_shown += 1;
+                        write_guard.wraps = wraps;
+                    }
                     sync_tx.send(true).expect("sync_tx send failed");
                 }
                 Err(..) => (),
@@ -160,23 +272,43 @@ fn main_thread(
 }
 
 fn main() {
-    let mut gui_settings = SettingsContainer::default();
-    let prefs_key = "config/gui";
-    if let Ok(loaded) = SettingsContainer::load(&APP_INFO, prefs_key) {
-        gui_settings = loaded;
-    } else {
-        let _ = gui_settings.save(&APP_INFO, prefs_key);
+    let cli = Cli::parse();
+    if let Some(Command::Analyze {
+        file,
+        wpm,
+        mode,
+        language,
+        json,
+    }) = &cli.command
+    {
+        let ok = analysis::run(file, mode.text_mode(), language.language(), *wpm, *json);
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+    let mut profiles = Profiles::load_or_default();
+    let stored_profile = profiles.active.clone();
+    if let Err(err) = cli.select_profile(&mut profiles) {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    }
+    let mut gui_settings = load_settings(&profiles.active);
+    let fullscreen = cli.fullscreen;
+    let tui = cli.tui;
+    let overrides = Overrides::apply(cli, &mut gui_settings, &stored_profile);
+    if tui && overrides.lesson().is_some() {
+        eprintln!("lesson files can only be opened in the window, not with --tui");
+        std::process::exit(2);
     }
 
     // shared state
     let running_lock = Arc::new(RwLock::new(false));
     let random_lock = Arc::new(RwLock::new(gui_settings.random));
     let rate_lock = Arc::new(RwLock::new(gui_settings.rate));
-    let word_lock = Arc::new(RwLock::new("Hallo!".to_string()));
+    let word_lock = Arc::new(RwLock::new(Entry::new("Hallo!")));
     let mode_lock = Arc::new(RwLock::new(false)); // false = word-per-line by default
+    let progress_lock = Arc::new(RwLock::new(Progress::default()));
 
     // channels
-    let (load_tx, load_rx): (Sender<PathBuf>, Receiver<PathBuf>) = mpsc::channel();
+    let (load_tx, load_rx): (Sender<LoadRequest>, Receiver<LoadRequest>) = mpsc::channel();
     let (step_tx, step_rx): (Sender<StepDir>, Receiver<StepDir>) = mpsc::channel();
     let (sync_tx, sync_rx): (Sender<bool>, Receiver<bool>) = mpsc::channel();
 
@@ -185,6 +317,7 @@ fn main() {
     let main_word_lock = word_lock.clone();
     let main_running_lock = running_lock.clone();
     let main_mode_lock = mode_lock.clone();
+    let main_progress_lock = progress_lock.clone();
 
     println!("starting main thread..");
     thread::spawn(move || {
@@ -194,15 +327,36 @@ fn main() {
             main_running_lock,
             main_mode_lock,
             main_word_lock,
+            main_progress_lock,
             step_rx,
             load_rx,
             sync_tx,
         );
     });
 
+    if tui {
+        let engine = tui::Engine {
+            rate_lock,
+            random_lock,
+            running_lock,
+            mode_lock,
+            word_lock,
+            progress_lock,
+            step_tx,
+            load_tx,
+            sync_rx,
+        };
+        if let Err(err) = tui::run(engine, gui_settings, profiles, overrides) {
+            eprintln!("terminal error: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // prepare UI
     let options = eframe::NativeOptions {
         viewport: ViewportBuilder::default()
+            .with_fullscreen(fullscreen)
             .with_drag_and_drop(true)
             .with_icon(
                 icon_data::from_png_bytes(&include_bytes!("../icons/icon.png")[..]).unwrap(),
@@ -215,15 +369,17 @@ fn main() {
     let gui_word_lock = word_lock.clone();
     let gui_running_lock = running_lock.clone();
     let gui_mode_lock = mode_lock.clone();
+    let gui_progress_lock = progress_lock.clone();
 
     // send initial file path to background thread
-    let _ = load_tx.send(gui_settings.file_path.clone());
+    let _ = load_tx.send(LoadRequest::from_settings(&gui_settings));
 
     eframe::run_native(
         "Word Nerd",
         options,
         Box::new(move |cc| {
             add_font(&cc.egui_ctx);
+            egui_extras::install_image_loaders(&cc.egui_ctx);
             let repaint_signal = cc.egui_ctx.clone();
             thread::spawn(move || loop {
                 if sync_rx.recv().is_ok() {
@@ -236,7 +392,10 @@ fn main() {
                 gui_running_lock,
                 gui_word_lock,
                 gui_mode_lock,
+                gui_progress_lock,
                 gui_settings,
+                profiles,
+                overrides,
                 step_tx,
                 load_tx,
             )))
@@ -0,0 +1,77 @@
+use eframe::egui::Vec2;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum Pattern {
+    Random,
+    Clockwise,
+    LeftRight,
+}
+
+impl Pattern {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Pattern::Random => "Zufällig",
+            Pattern::Clockwise => "Im Uhrzeigersinn",
+            Pattern::LeftRight => "Links/Rechts",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct PeripheralSettings {
+    // distance from the fixation cross in pixels
+    pub eccentricity: f32,
+    pub pattern: Pattern,
+    pub seed: u64,
+}
+
+impl PeripheralSettings {
+    pub fn default() -> PeripheralSettings {
+        PeripheralSettings {
+            eccentricity: 200.0,
+            pattern: Pattern::Random,
+            seed: 1,
+        }
+    }
+}
+
+// places the words around a fixation cross, the same seed gives the same sequence
+pub struct Peripheral {
+    rng: StdRng,
+    count: u32,
+    offset: Vec2,
+}
+
+impl Peripheral {
+    pub fn new(seed: u64) -> Peripheral {
+        Peripheral {
+            rng: StdRng::seed_from_u64(seed),
+            count: 0,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    pub fn next_position(&mut self, settings: &PeripheralSettings) {
+        let angle = match settings.pattern {
+            Pattern::Random => self.rng.random_range(0.0..TAU),
+            Pattern::Clockwise => self.count as f32 * TAU / 8.0,
+            Pattern::LeftRight => {
+                if self.count.is_multiple_of(2) {
+                    TAU / 2.0
+                } else {
+                    0.0
+                }
+            }
+        };
+        self.count += 1;
+        self.offset = Vec2::angled(angle) * settings.eccentricity;
+    }
+
+    pub fn offset(&self) -> Vec2 {
+        self.offset
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum Placement {
+    Above,
+    Instead,
+    PictureFirst,
+}
+
+impl Placement {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Placement::Above => "Bild über dem Wort",
+            Placement::Instead => "Nur Bild",
+            Placement::PictureFirst => "Erst Bild, dann Wort",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct PictureSettings {
+    pub placement: Placement,
+    pub picture_ms: u32,
+    // only used when the word follows the picture
+    pub word_ms: u32,
+}
+
+impl PictureSettings {
+    pub fn default() -> PictureSettings {
+        PictureSettings {
+            placement: Placement::Above,
+            picture_ms: 2000,
+            word_ms: 1500,
+        }
+    }
+
+    fn total(&self) -> Duration {
+        let ms = match self.placement {
+            Placement::PictureFirst => self.picture_ms + self.word_ms,
+            Placement::Above | Placement::Instead => self.picture_ms,
+        };
+        Duration::from_millis(ms as u64)
+    }
+}
+
+// what is visible of the current card
+pub struct CardView {
+    pub picture: bool,
+    pub word: bool,
+}
+
+// shows a picture with or before its word; timed per frame like the tachistoscope
+pub struct PictureCards {
+    running: bool,
+    shown_at: Option<Instant>,
+}
+
+impl PictureCards {
+    pub fn new() -> PictureCards {
+        PictureCards {
+            running: false,
+            shown_at: None,
+        }
+    }
+
+    pub fn start(&mut self) {
+        self.running = true;
+        self.shown_at = None;
+    }
+
+    pub fn stop(&mut self) {
+        self.running = false;
+        self.shown_at = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn card_shown(&mut self) {
+        if self.running {
+            self.shown_at = Some(Instant::now());
+        }
+    }
+
+    pub fn view(&self, settings: &PictureSettings) -> CardView {
+        match settings.placement {
+            Placement::Above => CardView {
+                picture: true,
+                word: true,
+            },
+            Placement::Instead => CardView {
+                picture: true,
+                word: false,
+            },
+            Placement::PictureFirst => {
+                let elapsed = self.shown_at.map(|t| t.elapsed()).unwrap_or_default();
+                let word = elapsed >= Duration::from_millis(settings.picture_ms as u64);
+                CardView {
+                    picture: !word,
+                    word,
+                }
+            }
+        }
+    }
+
+    // returns true when the next card is due
+    pub fn update(&mut self, settings: &PictureSettings) -> bool {
+        match self.shown_at {
+            Some(shown_at) if self.running && shown_at.elapsed() >= settings.total() => {
+                self.shown_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
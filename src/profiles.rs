@@ -0,0 +1,76 @@
+use crate::gui::SettingsContainer;
+use crate::APP_INFO;
+use preferences::Preferences;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PROFILE: &str = "Standard";
+const PROFILES_KEY: &str = "config/profiles";
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Profiles {
+    pub names: Vec<String>,
+    pub active: String,
+}
+
+impl Profiles {
+    pub fn default() -> Profiles {
+        Profiles {
+            names: vec![DEFAULT_PROFILE.to_string()],
+            active: DEFAULT_PROFILE.to_string(),
+        }
+    }
+
+    pub fn load_or_default() -> Profiles {
+        match Profiles::load(&APP_INFO, PROFILES_KEY) {
+            Ok(profiles) => profiles,
+            Err(_) => Profiles::default(),
+        }
+    }
+
+    pub fn store(&self) {
+        if let Err(err) = self.save(&APP_INFO, PROFILES_KEY) {
+            println!("profiles save failed: {:?}", err);
+        }
+    }
+
+    pub fn add(&mut self, name: &str) {
+        if !self.names.iter().any(|n| n == name) {
+            self.names.push(name.to_string());
+        }
+    }
+}
+
+pub fn is_valid_name(name: &str) -> bool {
+    // names end up in the preferences path, so only allow a safe subset
+    !name.trim().is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-')
+}
+
+pub fn profile_key(name: &str, item: &str) -> String {
+    if name == DEFAULT_PROFILE {
+        // the default profile keeps using the keys from before profiles existed
+        format!("config/{}", item)
+    } else {
+        format!("profiles/{}/{}", name.trim().replace(' ', "_"), item)
+    }
+}
+
+pub fn load_settings(name: &str) -> SettingsContainer {
+    let prefs_key = profile_key(name, "gui");
+    match SettingsContainer::load(&APP_INFO, &prefs_key) {
+        Ok(loaded) => loaded,
+        Err(_) => {
+            let gui_settings = SettingsContainer::default();
+            let _ = gui_settings.save(&APP_INFO, &prefs_key);
+            gui_settings
+        }
+    }
+}
+
+pub fn save_settings(name: &str, conf: &SettingsContainer) {
+    if let Err(err) = conf.save(&APP_INFO, profile_key(name, "gui")) {
+        println!("gui settings save failed: {:?}", err);
+    }
+}
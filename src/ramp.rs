@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum RampUnit {
+    Words,
+    Seconds,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct RampSettings {
+    pub start: f32,
+    pub step: f32,
+    pub every: u32,
+    pub unit: RampUnit,
+    pub target: f32,
+    // start again at the initial rate after a chapter (blank line or end of file)
+    pub drop_back: bool,
+}
+
+impl RampSettings {
+    pub fn default() -> RampSettings {
+        RampSettings {
+            start: 100.0,
+            step: 10.0,
+            every: 20,
+            unit: RampUnit::Words,
+            target: 300.0,
+            drop_back: false,
+        }
+    }
+}
+
+// progressive acceleration like in speed reading courses
+pub struct Ramp {
+    // kept apart from the normal reading rate, which is saved with the settings
+    pub rate: f32,
+    words: u32,
+    running_time: Duration,
+    running_since: Option<Instant>,
+}
+
+impl Ramp {
+    pub fn new(settings: &RampSettings) -> Ramp {
+        Ramp {
+            rate: settings.start,
+            words: 0,
+            running_time: Duration::ZERO,
+            running_since: None,
+        }
+    }
+
+    pub fn reset(&mut self, settings: &RampSettings) {
+        *self = Ramp::new(settings);
+    }
+
+    pub fn set_running(&mut self, running: bool) {
+        match (running, self.running_since) {
+            (true, None) => self.running_since = Some(Instant::now()),
+            (false, Some(since)) => {
+                self.running_time += since.elapsed();
+                self.running_since = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(since) => self.running_time + since.elapsed(),
+            None => self.running_time,
+        }
+    }
+
+    // returns the rate to use from now on
+    pub fn update(&mut self, settings: &RampSettings, words_shown: u64, chapter_end: bool) -> f32 {
+        if chapter_end && settings.drop_back {
+            self.running_time = Duration::ZERO;
+            self.running_since = self.running_since.map(|_| Instant::now());
+            self.words = 0;
+            self.rate = settings.start;
+            return self.rate;
+        }
+        self.words += words_shown as u32;
+        let due = match settings.unit {
+            RampUnit::Words => self.words >= settings.every,
+            RampUnit::Seconds => self.elapsed().as_secs() >= settings.every as u64,
+        };
+        if !due {
+            return self.rate;
+        }
+        self.words = 0;
+        self.running_time = Duration::ZERO;
+        self.running_since = self.running_since.map(|_| Instant::now());
+        self.rate = (self.rate + settings.step).min(settings.target.max(settings.start));
+        self.rate
+    }
+}
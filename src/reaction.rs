@@ -0,0 +1,72 @@
+use std::time::Instant;
+
+// shows every word until the reader confirms it and measures the recognition latency
+pub struct Reaction {
+    running: bool,
+    shown_at: Option<Instant>,
+    latencies: Vec<(String, f32)>,
+}
+
+impl Reaction {
+    pub fn new() -> Reaction {
+        Reaction {
+            running: false,
+            shown_at: None,
+            latencies: vec![],
+        }
+    }
+
+    pub fn start(&mut self) {
+        self.running = true;
+        self.shown_at = None;
+        self.latencies.clear();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn word_shown(&mut self) {
+        if self.running {
+            self.shown_at = Some(Instant::now());
+        }
+    }
+
+    pub fn confirm(&mut self, word: &str) -> bool {
+        // only count a word once, the next one is measured after it is drawn
+        match self.shown_at.take() {
+            Some(shown_at) if self.running => {
+                let latency_ms = shown_at.elapsed().as_secs_f32() * 1000.0;
+                self.latencies.push((word.to_string(), latency_ms));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn rolling_average(&self, window: usize) -> Option<f32> {
+        let recent: Vec<f32> = self
+            .latencies
+            .iter()
+            .rev()
+            .take(window.max(1))
+            .map(|(_, latency)| *latency)
+            .collect();
+        if recent.is_empty() {
+            None
+        } else {
+            Some(recent.iter().sum::<f32>() / recent.len() as f32)
+        }
+    }
+
+    pub fn finish(&mut self) -> Vec<(String, f32)> {
+        self.running = false;
+        self.shown_at = None;
+        std::mem::take(&mut self.latencies)
+    }
+}
+
+pub fn adapted_rate(average_latency_ms: f32) -> f32 {
+    // one word per recognition latency, limited to the range of the rate control
+    (60_000.0 / average_latency_ms.max(1.0)).clamp(10.0, 800.0)
+}
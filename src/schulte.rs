@@ -0,0 +1,176 @@
+use chrono::{DateTime, Local};
+use eframe::egui;
+use eframe::egui::{FontFamily, FontId, RichText, Vec2};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum SchulteSymbols {
+    Numbers,
+    Letters,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SchulteSettings {
+    pub size: usize,
+    pub symbols: SchulteSymbols,
+}
+
+impl SchulteSettings {
+    pub fn default() -> SchulteSettings {
+        SchulteSettings {
+            size: 5,
+            symbols: SchulteSymbols::Numbers,
+        }
+    }
+
+    fn max_size(&self) -> usize {
+        // the alphabet only fills a 5x5 table
+        match self.symbols {
+            SchulteSymbols::Numbers => 7,
+            SchulteSymbols::Letters => 5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SchulteResult {
+    pub date: DateTime<Local>,
+    pub size: usize,
+    pub symbols: SchulteSymbols,
+    pub seconds: f32,
+    pub mistakes: u32,
+}
+
+// a grid of shuffled symbols that have to be found in order
+pub struct SchulteTable {
+    settings: SchulteSettings,
+    cells: Vec<usize>,
+    next: usize,
+    started: Instant,
+    mistakes: u32,
+    finished: Option<f32>,
+}
+
+impl SchulteTable {
+    pub fn new(settings: &SchulteSettings) -> SchulteTable {
+        let mut settings = settings.clone();
+        settings.size = settings.size.clamp(3, settings.max_size());
+        let mut cells: Vec<usize> = (0..settings.size * settings.size).collect();
+        cells.shuffle(&mut rand::rng());
+        SchulteTable {
+            settings,
+            cells,
+            next: 0,
+            started: Instant::now(),
+            mistakes: 0,
+            finished: None,
+        }
+    }
+
+    fn symbol(&self, value: usize) -> String {
+        match self.settings.symbols {
+            SchulteSymbols::Numbers => (value + 1).to_string(),
+            SchulteSymbols::Letters => ((b'A' + value as u8) as char).to_string(),
+        }
+    }
+
+    fn select(&mut self, value: usize) -> Option<SchulteResult> {
+        if self.finished.is_some() {
+            return None;
+        }
+        if value != self.next {
+            self.mistakes += 1;
+            return None;
+        }
+        self.next += 1;
+        if self.next < self.cells.len() {
+            return None;
+        }
+        let seconds = self.started.elapsed().as_secs_f32();
+        self.finished = Some(seconds);
+        Some(SchulteResult {
+            date: Local::now(),
+            size: self.settings.size,
+            symbols: self.settings.symbols,
+            seconds,
+            mistakes: self.mistakes,
+        })
+    }
+}
+
+pub fn schulte_window(
+    ctx: &egui::Context,
+    open: &mut bool,
+    settings: &mut SchulteSettings,
+    table: &mut SchulteTable,
+    font_size: f32,
+) -> Option<SchulteResult> {
+    let mut result = None;
+    egui::Window::new("Schulte-Tabelle")
+        .open(open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let (max_size, size) = (settings.max_size(), settings.size);
+                ui.add(
+                    egui::DragValue::new(&mut settings.size)
+                        .range(3..=max_size)
+                        .suffix(format!("×{}", size)),
+                );
+                ui.selectable_value(&mut settings.symbols, SchulteSymbols::Numbers, "1 2 3");
+                ui.selectable_value(&mut settings.symbols, SchulteSymbols::Letters, "A B C");
+                settings.size = settings.size.min(settings.max_size());
+                if ui.button("Neu").clicked() || *settings != table.settings {
+                    *table = SchulteTable::new(settings);
+                }
+            });
+            ui.add_space(5.0);
+
+            match table.finished {
+                Some(seconds) => ui.label(
+                    RichText::new(format!(
+                        "Geschafft in {:.1} s mit {} Fehlern",
+                        seconds, table.mistakes
+                    ))
+                    .strong(),
+                ),
+                None => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                    ui.label(format!(
+                        "Suche: {}   {:.0} s   Fehler: {}",
+                        table.symbol(table.next),
+                        table.started.elapsed().as_secs_f32(),
+                        table.mistakes
+                    ))
+                }
+            };
+            ui.add_space(5.0);
+
+            let cell = font_size.clamp(40.0, 90.0);
+            let font_id = FontId::new(cell * 0.5, FontFamily::Name("my_font".into()));
+            let mut clicked = None;
+            egui::Grid::new("schulte_grid")
+                .spacing(Vec2::splat(4.0))
+                .show(ui, |ui| {
+                    for (n, value) in table.cells.iter().enumerate() {
+                        let text = RichText::new(table.symbol(*value)).font(font_id.clone());
+                        // symbols that were already found stay highlighted
+                        let button = egui::Button::new(text)
+                            .min_size(Vec2::splat(cell))
+                            .selected(*value < table.next);
+                        if ui.add(button).clicked() {
+                            clicked = Some(*value);
+                        }
+                        if (n + 1).is_multiple_of(table.settings.size) {
+                            ui.end_row();
+                        }
+                    }
+                });
+            if let Some(value) = clicked {
+                result = table.select(value);
+            }
+        });
+    result
+}
@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum SpeechTiming {
+    Off,
+    // spoken first, the word follows after the delay
+    Before,
+    // shown first, spoken after the delay
+    After,
+    // only spoken, e.g. for dictation
+    Instead,
+}
+
+impl SpeechTiming {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SpeechTiming::Off => "Aus",
+            SpeechTiming::Before => "Vor dem Wort",
+            SpeechTiming::After => "Nach dem Wort",
+            SpeechTiming::Instead => "Statt dem Wort",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SpeechSettings {
+    pub timing: SpeechTiming,
+    pub voice: String,
+    pub delay_ms: u32,
+}
+
+impl SpeechSettings {
+    pub fn default() -> SpeechSettings {
+        SpeechSettings {
+            timing: SpeechTiming::Off,
+            voice: "de".to_string(),
+            delay_ms: 1000,
+        }
+    }
+}
+
+pub trait SpeechBackend {
+    fn speak(&mut self, text: &str, voice: &str) -> io::Result<()>;
+    fn stop(&mut self);
+}
+
+// calls a locally installed espeak-ng, one process per word
+pub struct EspeakBackend {
+    program: String,
+    child: Option<Child>,
+}
+
+impl EspeakBackend {
+    pub fn new() -> EspeakBackend {
+        EspeakBackend {
+            program: "espeak-ng".to_string(),
+            child: None,
+        }
+    }
+}
+
+impl SpeechBackend for EspeakBackend {
+    fn speak(&mut self, text: &str, voice: &str) -> io::Result<()> {
+        // a new word interrupts the previous one
+        self.stop();
+        let child = Command::new(&self.program)
+            .args(["-v", voice, "--", text])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        self.child = Some(child);
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            if let Ok(None) = child.try_wait() {
+                let _ = child.kill();
+            }
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for EspeakBackend {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// decides when the current word is spoken and whether it is visible
+pub struct Dictation {
+    backend: Box<dyn SpeechBackend>,
+    word: Option<String>,
+    shown_at: Option<Instant>,
+    failed: bool,
+}
+
+impl Dictation {
+    pub fn new(backend: Box<dyn SpeechBackend>) -> Dictation {
+        Dictation {
+            backend,
+            word: None,
+            shown_at: None,
+            failed: false,
+        }
+    }
+
+    fn speak(&mut self, text: &str, settings: &SpeechSettings) {
+        match self.backend.speak(text, &settings.voice) {
+            Ok(()) => self.failed = false,
+            Err(err) => {
+                // report once, not for every word
+                if !self.failed {
+                    println!("speech failed: {:?}", err);
+                }
+                self.failed = true;
+            }
+        }
+    }
+
+    pub fn word_shown(&mut self, word: &str, settings: &SpeechSettings) {
+        self.shown_at = Some(Instant::now());
+        self.word = None;
+        if settings.timing == SpeechTiming::Off || word.trim().is_empty() {
+            return;
+        }
+        match settings.timing {
+            SpeechTiming::Before | SpeechTiming::Instead => self.speak(word, settings),
+            _ => self.word = Some(word.to_string()),
+        }
+    }
+
+    fn delay_over(&self, settings: &SpeechSettings) -> bool {
+        self.shown_at
+            .is_none_or(|t| t.elapsed() >= Duration::from_millis(settings.delay_ms as u64))
+    }
+
+    // speaks a word that is due and returns true while still waiting for it
+    pub fn update(&mut self, settings: &SpeechSettings) -> bool {
+        if self.word.is_none() {
+            return settings.timing == SpeechTiming::Before && !self.delay_over(settings);
+        }
+        if settings.timing != SpeechTiming::After {
+            self.word = None;
+            return false;
+        }
+        if !self.delay_over(settings) {
+            return true;
+        }
+        if let Some(word) = self.word.take() {
+            self.speak(&word, settings);
+        }
+        false
+    }
+
+    pub fn word_visible(&self, settings: &SpeechSettings) -> bool {
+        match settings.timing {
+            SpeechTiming::Off | SpeechTiming::After => true,
+            SpeechTiming::Before => self.delay_over(settings),
+            SpeechTiming::Instead => false,
+        }
+    }
+
+    pub fn has_failed(&self) -> bool {
+        self.failed
+    }
+
+    pub fn stop(&mut self) {
+        self.word = None;
+        self.shown_at = None;
+        self.backend.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    // records the spoken words instead of playing them
+    struct MockSpeech {
+        spoken: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl SpeechBackend for MockSpeech {
+        fn speak(&mut self, text: &str, _voice: &str) -> io::Result<()> {
+            self.spoken.lock().unwrap().push(text.to_string());
+            Ok(())
+        }
+
+        fn stop(&mut self) {}
+    }
+
+    fn dictation() -> (Dictation, Arc<Mutex<Vec<String>>>) {
+        let spoken = Arc::new(Mutex::new(vec![]));
+        let backend = MockSpeech {
+            spoken: spoken.clone(),
+        };
+        (Dictation::new(Box::new(backend)), spoken)
+    }
+
+    fn settings(timing: SpeechTiming, delay_ms: u32) -> SpeechSettings {
+        SpeechSettings {
+            timing,
+            voice: "de".to_string(),
+            delay_ms,
+        }
+    }
+
+    #[test]
+    fn off_speaks_nothing() {
+        let (mut dictation, spoken) = dictation();
+        let settings = settings(SpeechTiming::Off, 0);
+        dictation.word_shown("Haus", &settings);
+        dictation.update(&settings);
+        assert!(spoken.lock().unwrap().is_empty());
+        assert!(dictation.word_visible(&settings));
+    }
+
+    #[test]
+    fn before_speaks_first_and_hides_the_word() {
+        let (mut dictation, spoken) = dictation();
+        let settings = settings(SpeechTiming::Before, 60_000);
+        dictation.word_shown("Haus", &settings);
+        assert_eq!(*spoken.lock().unwrap(), vec!["Haus"]);
+        assert!(!dictation.word_visible(&settings));
+        assert!(dictation.update(&settings));
+    }
+
+    #[test]
+    fn after_waits_for_the_delay() {
+        let (mut dictation, spoken) = dictation();
+        let waiting = settings(SpeechTiming::After, 60_000);
+        dictation.word_shown("Maus", &waiting);
+        assert!(dictation.update(&waiting));
+        assert!(spoken.lock().unwrap().is_empty());
+        assert!(dictation.word_visible(&waiting));
+
+        let due = settings(SpeechTiming::After, 0);
+        assert!(!dictation.update(&due));
+        assert!(!dictation.update(&due));
+        assert_eq!(*spoken.lock().unwrap(), vec!["Maus"]);
+    }
+
+    #[test]
+    fn instead_never_shows_the_word() {
+        let (mut dictation, spoken) = dictation();
+        let settings = settings(SpeechTiming::Instead, 0);
+        dictation.word_shown("Baum", &settings);
+        dictation.word_shown(" ", &settings);
+        assert_eq!(*spoken.lock().unwrap(), vec!["Baum"]);
+        assert!(!dictation.word_visible(&settings));
+    }
+}
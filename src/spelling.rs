@@ -0,0 +1,174 @@
+use eframe::egui::text::{LayoutJob, TextFormat};
+use eframe::egui::{FontId, Stroke, Visuals};
+use std::time::{Duration, Instant};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Mark {
+    Correct,
+    // typed instead of the expected character
+    Wrong,
+    // expected but not typed
+    Missing,
+    // typed but not expected
+    Extra,
+}
+
+// aligns the input with the word (fewest edits) and marks every character
+pub fn compare(word: &str, input: &str) -> Vec<(char, Mark)> {
+    let word: Vec<char> = word.chars().collect();
+    let input: Vec<char> = input.chars().collect();
+    let mut dist = vec![vec![0usize; input.len() + 1]; word.len() + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=word.len() {
+        for j in 1..=input.len() {
+            let cost = usize::from(word[i - 1] != input[j - 1]);
+            dist[i][j] = (dist[i - 1][j - 1] + cost)
+                .min(dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1);
+        }
+    }
+
+    let mut marks = vec![];
+    let (mut i, mut j) = (word.len(), input.len());
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 {
+            let cost = usize::from(word[i - 1] != input[j - 1]);
+            if dist[i][j] == dist[i - 1][j - 1] + cost {
+                let mark = if cost == 0 {
+                    Mark::Correct
+                } else {
+                    Mark::Wrong
+                };
+                marks.push((input[j - 1], mark));
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+        if i > 0 && dist[i][j] == dist[i - 1][j] + 1 {
+            marks.push((word[i - 1], Mark::Missing));
+            i -= 1;
+        } else {
+            marks.push((input[j - 1], Mark::Extra));
+            j -= 1;
+        }
+    }
+    marks.reverse();
+    marks
+}
+
+// share of correct characters in percent
+pub fn accuracy(marks: &[(char, Mark)]) -> f32 {
+    if marks.is_empty() {
+        return 100.0;
+    }
+    let correct = marks.iter().filter(|(_, m)| *m == Mark::Correct).count();
+    100.0 * correct as f32 / marks.len() as f32
+}
+
+pub fn diff_layout(marks: &[(char, Mark)], font_id: FontId, visuals: &Visuals) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    for (c, mark) in marks {
+        let mut format = TextFormat::simple(font_id.clone(), visuals.strong_text_color());
+        match mark {
+            Mark::Correct => {}
+            Mark::Wrong => format.color = visuals.error_fg_color,
+            Mark::Missing => {
+                format.color = visuals.weak_text_color();
+                format.underline = Stroke::new(2.0, visuals.error_fg_color);
+            }
+            Mark::Extra => {
+                format.color = visuals.error_fg_color;
+                format.strikethrough = Stroke::new(2.0, visuals.error_fg_color);
+            }
+        }
+        job.append(&c.to_string(), 0.0, format);
+    }
+    job
+}
+
+// flashes the word, hides it and waits for the learner to type it
+pub struct Spelling {
+    running: bool,
+    shown_at: Option<Instant>,
+    pub input: String,
+    result: Option<Vec<(char, Mark)>>,
+    accuracies: Vec<f32>,
+}
+
+impl Spelling {
+    pub fn new() -> Spelling {
+        Spelling {
+            running: false,
+            shown_at: None,
+            input: String::new(),
+            result: None,
+            accuracies: vec![],
+        }
+    }
+
+    pub fn start(&mut self) {
+        *self = Spelling::new();
+        self.running = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.running = false;
+        self.shown_at = None;
+        self.result = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn word_shown(&mut self) {
+        if self.running {
+            self.shown_at = Some(Instant::now());
+            self.input.clear();
+            self.result = None;
+        }
+    }
+
+    // time left until the word is hidden, None once it is
+    pub fn time_to_hide(&self, flash_ms: u32) -> Option<Duration> {
+        let shown_at = self.shown_at?;
+        Duration::from_millis(flash_ms as u64)
+            .checked_sub(shown_at.elapsed())
+            .filter(|d| !d.is_zero())
+    }
+
+    pub fn is_hidden(&self, flash_ms: u32) -> bool {
+        self.shown_at.is_some() && self.time_to_hide(flash_ms).is_none()
+    }
+
+    // compares the input with the word and returns the accuracy
+    pub fn check(&mut self, word: &str) -> f32 {
+        let marks = compare(word.trim(), self.input.trim());
+        let accuracy = accuracy(&marks);
+        self.accuracies.push(accuracy);
+        self.result = Some(marks);
+        accuracy
+    }
+
+    pub fn result(&self) -> Option<&[(char, Mark)]> {
+        self.result.as_deref()
+    }
+
+    pub fn words(&self) -> usize {
+        self.accuracies.len()
+    }
+
+    pub fn average_accuracy(&self) -> Option<f32> {
+        if self.accuracies.is_empty() {
+            None
+        } else {
+            Some(self.accuracies.iter().sum::<f32>() / self.accuracies.len() as f32)
+        }
+    }
+}
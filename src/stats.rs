@@ -0,0 +1,497 @@
+use crate::assessment::AssessmentResult;
+use crate::flashcards::CardStats;
+use crate::profiles::profile_key;
+use crate::schulte::{SchulteResult, SchulteSymbols};
+use crate::APP_INFO;
+use chrono::{DateTime, Local, NaiveDate};
+use eframe::egui;
+use eframe::egui::{Pos2, RichText, Sense, Shape, Stroke, Vec2};
+use preferences::Preferences;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Session {
+    pub learner: String,
+    pub file: String,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub words_shown: u64,
+    pub average_wpm: f32,
+    pub pauses: u32,
+    pub steps_backward: u32,
+    // rate at the start and the highest rate reached during a speed ramp
+    #[serde(default)]
+    pub ramp_start_rate: Option<f32>,
+    #[serde(default)]
+    pub ramp_max_rate: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct History {
+    pub sessions: Vec<Session>,
+    #[serde(default)]
+    pub assessments: Vec<AssessmentResult>,
+    // recognition latencies in milliseconds from the reaction time exercise
+    #[serde(default)]
+    pub latencies: BTreeMap<String, Vec<f32>>,
+    #[serde(default)]
+    pub schulte: Vec<SchulteResult>,
+    #[serde(default)]
+    pub cards: BTreeMap<String, CardStats>,
+    // accuracy in percent of every spelling attempt
+    #[serde(default)]
+    pub spelling: BTreeMap<String, Vec<f32>>,
+}
+
+impl History {
+    pub fn load_for(learner: &str) -> History {
+        History::load(&APP_INFO, profile_key(learner, "history")).unwrap_or_default()
+    }
+
+    pub fn store_for(&self, learner: &str) {
+        if let Err(err) = self.save(&APP_INFO, profile_key(learner, "history")) {
+            println!("history save failed: {:?}", err);
+        }
+    }
+
+    pub fn record_latency(&mut self, word: &str, latency_ms: f32) {
+        self.latencies
+            .entry(word.to_string())
+            .or_default()
+            .push(latency_ms);
+    }
+
+    pub fn record_spelling(&mut self, word: &str, accuracy: f32) {
+        self.spelling
+            .entry(word.to_string())
+            .or_default()
+            .push(accuracy);
+    }
+
+    pub fn daily_totals(&self) -> Vec<DailyTotal> {
+        let mut days: BTreeMap<NaiveDate, DailyTotal> = BTreeMap::new();
+        for session in self.sessions.iter() {
+            let day = session.start.date_naive();
+            let total = days.entry(day).or_insert(DailyTotal {
+                day,
+                sessions: 0,
+                words_shown: 0,
+                minutes: 0.0,
+                average_wpm: 0.0,
+            });
+            total.sessions += 1;
+            total.words_shown += session.words_shown;
+            total.minutes += (session.end - session.start).num_seconds() as f32 / 60.0;
+            total.average_wpm += session.average_wpm;
+        }
+        days.into_values()
+            .map(|mut total| {
+                total.average_wpm /= total.sessions as f32;
+                total
+            })
+            .collect()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            "learner,file,start,end,words_shown,average_wpm,pauses,steps_backward,ramp_start_rate,ramp_max_rate\n".to_string();
+        for s in self.sessions.iter() {
+            csv += &format!(
+                "{},{},{},{},{},{:.1},{},{},{},{}\n",
+                csv_field(&s.learner),
+                csv_field(&s.file),
+                s.start.to_rfc3339(),
+                s.end.to_rfc3339(),
+                s.words_shown,
+                s.average_wpm,
+                s.pauses,
+                s.steps_backward,
+                s.ramp_start_rate.map(|r| r.to_string()).unwrap_or_default(),
+                s.ramp_max_rate.map(|r| r.to_string()).unwrap_or_default()
+            );
+        }
+        csv
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub struct DailyTotal {
+    pub day: NaiveDate,
+    pub sessions: u32,
+    pub words_shown: u64,
+    pub minutes: f32,
+    pub average_wpm: f32,
+}
+
+// collects the numbers of the running session until it is finished
+pub struct SessionTracker {
+    start: Option<DateTime<Local>>,
+    running_since: Option<Instant>,
+    reading_time: Duration,
+    words_shown: u64,
+    running_words: u64,
+    pauses: u32,
+    steps_backward: u32,
+    ramp_rates: Option<(f32, f32)>,
+}
+
+impl SessionTracker {
+    pub fn new() -> SessionTracker {
+        SessionTracker {
+            start: None,
+            running_since: None,
+            reading_time: Duration::ZERO,
+            words_shown: 0,
+            running_words: 0,
+            pauses: 0,
+            steps_backward: 0,
+            ramp_rates: None,
+        }
+    }
+
+    pub fn set_running(&mut self, running: bool) {
+        match (running, self.running_since) {
+            (true, None) => self.running_since = Some(Instant::now()),
+            (false, Some(since)) => {
+                self.reading_time += since.elapsed();
+                self.running_since = None;
+                self.pauses += 1;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn words_shown(&mut self, count: u64) {
+        if count == 0 {
+            return;
+        }
+        if self.start.is_none() {
+            self.start = Some(Local::now());
+        }
+        self.words_shown += count;
+        if self.running_since.is_some() {
+            self.running_words += count;
+        }
+    }
+
+    pub fn step_backward(&mut self) {
+        self.steps_backward += 1;
+    }
+
+    pub fn ramp_rate(&mut self, rate: f32) {
+        self.ramp_rates = match self.ramp_rates {
+            Some((start, max)) => Some((start, max.max(rate))),
+            None => Some((rate, rate)),
+        };
+    }
+
+    pub fn finish(&mut self, learner: &str, file: &Path) -> Option<Session> {
+        let mut reading_time = self.reading_time;
+        if let Some(since) = self.running_since {
+            reading_time += since.elapsed();
+        }
+        let average_wpm = if reading_time.as_secs_f32() > 0.0 {
+            self.running_words as f32 / reading_time.as_secs_f32() * 60.0
+        } else {
+            0.0
+        };
+        let session = self.start.map(|start| Session {
+            learner: learner.to_string(),
+            file: file.display().to_string(),
+            start,
+            end: Local::now(),
+            words_shown: self.words_shown,
+            average_wpm,
+            pauses: self.pauses,
+            steps_backward: self.steps_backward,
+            ramp_start_rate: self.ramp_rates.map(|(start, _)| start),
+            ramp_max_rate: self.ramp_rates.map(|(_, max)| max),
+        });
+        *self = SessionTracker::new();
+        session
+    }
+}
+
+fn trend_plot(ui: &mut egui::Ui, totals: &[DailyTotal]) {
+    let (rect, _response) =
+        ui.allocate_exact_size(Vec2::new(ui.available_width(), 150.0), Sense::hover());
+    let painter = ui.painter_at(rect);
+    let stroke = Stroke::new(1.0, ui.visuals().weak_text_color());
+    painter.rect_stroke(rect, 0.0, stroke, egui::StrokeKind::Inside);
+
+    let max_wpm = totals.iter().map(|t| t.average_wpm).fold(10.0, f32::max);
+    let step = rect.width() / (totals.len().max(2) - 1) as f32;
+    let points: Vec<Pos2> = totals
+        .iter()
+        .enumerate()
+        .map(|(n, t)| {
+            Pos2::new(
+                rect.left() + n as f32 * step,
+                rect.bottom() - t.average_wpm / max_wpm * (rect.height() - 10.0),
+            )
+        })
+        .collect();
+    painter.text(
+        rect.left_top() + Vec2::new(4.0, 2.0),
+        egui::Align2::LEFT_TOP,
+        format!("{:.0} wpm", max_wpm),
+        egui::FontId::proportional(12.0),
+        ui.visuals().weak_text_color(),
+    );
+    let line = Stroke::new(2.0, ui.visuals().selection.bg_fill);
+    painter.add(Shape::line(points.clone(), line));
+    for p in points {
+        painter.circle_filled(p, 3.0, line.color);
+    }
+}
+
+fn sessions_section(ui: &mut egui::Ui, history: &History) {
+    let totals = history.daily_totals();
+    if totals.is_empty() {
+        ui.label("Noch keine Lesesitzungen aufgezeichnet.");
+        return;
+    }
+
+    ui.label(RichText::new("Lesegeschwindigkeit pro Tag").strong());
+    trend_plot(ui, &totals);
+    ui.add_space(10.0);
+
+    egui::ScrollArea::vertical()
+        .id_salt("daily_totals")
+        .max_height(250.0)
+        .show(ui, |ui| {
+            egui::Grid::new("daily_totals")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Tag");
+                    ui.strong("Sitzungen");
+                    ui.strong("Wörter");
+                    ui.strong("Minuten");
+                    ui.strong("⌀ wpm");
+                    ui.end_row();
+                    for t in totals.iter().rev() {
+                        ui.label(t.day.format("%d.%m.%Y").to_string());
+                        ui.label(t.sessions.to_string());
+                        ui.label(t.words_shown.to_string());
+                        ui.label(format!("{:.1}", t.minutes));
+                        ui.label(format!("{:.0}", t.average_wpm));
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+fn assessments_section(ui: &mut egui::Ui, history: &History) {
+    egui::ScrollArea::vertical()
+        .id_salt("assessments")
+        .max_height(200.0)
+        .show(ui, |ui| {
+            egui::Grid::new("assessments").striped(true).show(ui, |ui| {
+                ui.strong("Datum");
+                ui.strong("Datei");
+                ui.strong("WCPM");
+                ui.strong("Genauigkeit");
+                ui.strong("Fehler");
+                ui.end_row();
+                for a in history.assessments.iter().rev() {
+                    ui.label(a.date.format("%d.%m.%Y %H:%M").to_string());
+                    ui.label(file_name(&a.file));
+                    ui.label(format!("{:.0}", a.wcpm));
+                    ui.label(format!("{:.0} %", a.accuracy));
+                    ui.label(a.errors.join(", "));
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+fn latencies_section(ui: &mut egui::Ui, history: &History) {
+    let mut words: Vec<(&String, f32, usize)> = history
+        .latencies
+        .iter()
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(word, l)| (word, l.iter().sum::<f32>() / l.len() as f32, l.len()))
+        .collect();
+    // slowest words first, they need the most practice
+    words.sort_by(|a, b| b.1.total_cmp(&a.1));
+    egui::ScrollArea::vertical()
+        .id_salt("latencies")
+        .max_height(200.0)
+        .show(ui, |ui| {
+            egui::Grid::new("latencies").striped(true).show(ui, |ui| {
+                ui.strong("Wort");
+                ui.strong("⌀ ms");
+                ui.strong("Anzahl");
+                ui.end_row();
+                for (word, average, count) in words {
+                    ui.label(word);
+                    ui.label(format!("{:.0}", average));
+                    ui.label(count.to_string());
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+fn schulte_section(ui: &mut egui::Ui, history: &History) {
+    egui::ScrollArea::vertical()
+        .id_salt("schulte")
+        .max_height(200.0)
+        .show(ui, |ui| {
+            egui::Grid::new("schulte").striped(true).show(ui, |ui| {
+                ui.strong("Datum");
+                ui.strong("Tabelle");
+                ui.strong("Zeit");
+                ui.strong("Fehler");
+                ui.end_row();
+                for r in history.schulte.iter().rev() {
+                    ui.label(r.date.format("%d.%m.%Y %H:%M").to_string());
+                    ui.label(format!(
+                        "{}×{} {}",
+                        r.size,
+                        r.size,
+                        match r.symbols {
+                            SchulteSymbols::Numbers => "Zahlen",
+                            SchulteSymbols::Letters => "Buchstaben",
+                        }
+                    ));
+                    ui.label(format!("{:.1} s", r.seconds));
+                    ui.label(r.mistakes.to_string());
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+fn cards_section(ui: &mut egui::Ui, history: &History) {
+    let mut cards: Vec<(&String, &CardStats)> = history.cards.iter().collect();
+    // cards that were missed most often first
+    cards.sort_by_key(|(_, c)| std::cmp::Reverse(c.unknown));
+    egui::ScrollArea::vertical()
+        .id_salt("cards")
+        .max_height(200.0)
+        .show(ui, |ui| {
+            egui::Grid::new("cards").striped(true).show(ui, |ui| {
+                ui.strong("Karte");
+                ui.strong("Gewusst");
+                ui.strong("Nicht gewusst");
+                ui.end_row();
+                for (card, stats) in cards {
+                    ui.label(card);
+                    ui.label(stats.known.to_string());
+                    ui.label(stats.unknown.to_string());
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+fn spelling_section(ui: &mut egui::Ui, history: &History) {
+    let mut words: Vec<(&String, f32, usize)> = history
+        .spelling
+        .iter()
+        .filter(|(_, a)| !a.is_empty())
+        .map(|(word, a)| (word, a.iter().sum::<f32>() / a.len() as f32, a.len()))
+        .collect();
+    // words with the most mistakes first
+    words.sort_by(|a, b| a.1.total_cmp(&b.1));
+    egui::ScrollArea::vertical()
+        .id_salt("spelling")
+        .max_height(200.0)
+        .show(ui, |ui| {
+            egui::Grid::new("spelling").striped(true).show(ui, |ui| {
+                ui.strong("Wort");
+                ui.strong("⌀ richtig");
+                ui.strong("Anzahl");
+                ui.end_row();
+                for (word, accuracy, count) in words {
+                    ui.label(word);
+                    ui.label(format!("{:.0} %", accuracy));
+                    ui.label(count.to_string());
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+pub fn statistics_window(ctx: &egui::Context, open: &mut bool, learner: &str, history: &History) {
+    egui::Window::new(format!("Statistik – {}", learner))
+        .open(open)
+        .default_width(500.0)
+        .show(ctx, |ui| {
+            sessions_section(ui, history);
+
+            if !history.assessments.is_empty() {
+                ui.add_space(10.0);
+                egui::CollapsingHeader::new("Lesetests")
+                    .default_open(true)
+                    .show(ui, |ui| assessments_section(ui, history));
+            }
+
+            if !history.latencies.is_empty() {
+                ui.add_space(10.0);
+                egui::CollapsingHeader::new("Erkennungszeiten")
+                    .default_open(false)
+                    .show(ui, |ui| latencies_section(ui, history));
+            }
+
+            if !history.schulte.is_empty() {
+                ui.add_space(10.0);
+                egui::CollapsingHeader::new("Schulte-Tabellen")
+                    .default_open(false)
+                    .show(ui, |ui| schulte_section(ui, history));
+            }
+
+            if !history.cards.is_empty() {
+                ui.add_space(10.0);
+                egui::CollapsingHeader::new("Karteikarten")
+                    .default_open(false)
+                    .show(ui, |ui| cards_section(ui, history));
+            }
+
+            if !history.spelling.is_empty() {
+                ui.add_space(10.0);
+                egui::CollapsingHeader::new("Rechtschreibung")
+                    .default_open(false)
+                    .show(ui, |ui| spelling_section(ui, history));
+            }
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button("Als CSV exportieren").clicked() {
+                    export(&format!("{}_statistik.csv", learner), history.to_csv());
+                }
+                if ui.button("Als JSON exportieren").clicked() {
+                    match serde_json::to_string_pretty(history) {
+                        Ok(json) => export(&format!("{}_statistik.json", learner), json),
+                        Err(err) => println!("history serialization failed: {:?}", err),
+                    }
+                }
+            });
+        });
+}
+
+fn export(file_name: &str, content: String) {
+    if let Some(path) = rfd::FileDialog::new().set_file_name(file_name).save_file() {
+        if let Err(err) = std::fs::write(&path, content) {
+            println!("exporting statistics failed: {:?}", err);
+        }
+    }
+}
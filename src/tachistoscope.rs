@@ -0,0 +1,123 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum MaskStyle {
+    Hashes,
+    RandomLetters,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct TachistoscopeSettings {
+    pub exposure_ms: u32,
+    pub mask_ms: u32,
+    pub blank_ms: u32,
+    pub mask: MaskStyle,
+}
+
+impl TachistoscopeSettings {
+    pub fn default() -> TachistoscopeSettings {
+        TachistoscopeSettings {
+            exposure_ms: 100,
+            mask_ms: 200,
+            blank_ms: 500,
+            mask: MaskStyle::Hashes,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Phase {
+    Waiting,
+    Exposure,
+    Mask,
+    Blank,
+}
+
+// flashes a word, covers it with a mask and pauses; timed per frame instead of the background thread
+pub struct Tachistoscope {
+    running: bool,
+    phase: Phase,
+    phase_start: Instant,
+    mask_text: String,
+}
+
+impl Tachistoscope {
+    pub fn new() -> Tachistoscope {
+        Tachistoscope {
+            running: false,
+            phase: Phase::Waiting,
+            phase_start: Instant::now(),
+            mask_text: String::new(),
+        }
+    }
+
+    pub fn start(&mut self) {
+        self.running = true;
+        self.phase = Phase::Waiting;
+    }
+
+    pub fn stop(&mut self) {
+        self.running = false;
+        self.phase = Phase::Waiting;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn word_shown(&mut self, word: &str, style: MaskStyle) {
+        if self.running {
+            self.mask_text = mask_for(word, style);
+            self.phase = Phase::Exposure;
+            self.phase_start = Instant::now();
+        }
+    }
+
+    // advances the phases and returns true when the next word is due
+    pub fn update(&mut self, settings: &TachistoscopeSettings, frame_time: f32) -> bool {
+        if !self.running {
+            return false;
+        }
+        let duration = match self.phase {
+            Phase::Waiting => return false,
+            Phase::Exposure => settings.exposure_ms,
+            Phase::Mask => settings.mask_ms,
+            Phase::Blank => settings.blank_ms,
+        };
+        // switch on the frame closest to the end of the phase
+        let half_frame = Duration::from_secs_f32(frame_time / 2.0);
+        if self.phase_start.elapsed() + half_frame < Duration::from_millis(duration as u64) {
+            return false;
+        }
+        self.phase_start = Instant::now();
+        self.phase = match self.phase {
+            Phase::Exposure => Phase::Mask,
+            Phase::Mask => Phase::Blank,
+            _ => Phase::Waiting,
+        };
+        self.phase == Phase::Waiting
+    }
+
+    pub fn display<'a>(&'a self, word: &'a str) -> &'a str {
+        match self.phase {
+            Phase::Exposure => word,
+            Phase::Mask => &self.mask_text,
+            Phase::Waiting | Phase::Blank => "",
+        }
+    }
+}
+
+pub fn mask_for(word: &str, style: MaskStyle) -> String {
+    let len = word.chars().count().max(1);
+    match style {
+        MaskStyle::Hashes => "#".repeat(len),
+        MaskStyle::RandomLetters => {
+            let mut rng = rand::rng();
+            (0..len)
+                .map(|_| rng.random_range(b'a'..=b'z') as char)
+                .collect()
+        }
+    }
+}
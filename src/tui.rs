@@ -0,0 +1,362 @@
+use crate::cli::Overrides;
+use crate::gui::{LoadRequest, Progress, SettingsContainer, StepDir};
+use crate::io::Entry;
+use crate::profiles::{save_settings, Profiles};
+use crate::stats::{History, SessionTracker};
+use crate::watch::FileWatch;
+use crate::FONT;
+use ab_glyph::{point, Font, FontRef, PxScale, ScaleFont};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, queue, style, terminal};
+use std::io::{self, Write};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+// the same locks and channels the GUI uses to talk to the background thread
+pub struct Engine {
+    pub rate_lock: Arc<RwLock<f32>>,
+    pub random_lock: Arc<RwLock<bool>>,
+    pub running_lock: Arc<RwLock<bool>>,
+    pub mode_lock: Arc<RwLock<bool>>,
+    pub word_lock: Arc<RwLock<Entry>>,
+    pub progress_lock: Arc<RwLock<Progress>>,
+    pub step_tx: Sender<StepDir>,
+    pub load_tx: Sender<LoadRequest>,
+    pub sync_rx: Receiver<bool>,
+}
+
+struct Bitmap {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    fn get(&self, x: i64, y: usize) -> bool {
+        x >= 0 && (x as usize) < self.width && self.pixels[y * self.width + x as usize]
+    }
+
+    // two pixels per character cell, drawn with half blocks
+    fn lines(&self, columns: usize, column_of: impl Fn(usize) -> i64) -> Vec<String> {
+        (0..self.height.div_ceil(2))
+            .map(|row| {
+                (0..columns)
+                    .map(|c| {
+                        let x = column_of(c);
+                        let top = self.get(x, row * 2);
+                        let bottom = row * 2 + 1 < self.height && self.get(x, row * 2 + 1);
+                        match (top, bottom) {
+                            (true, true) => '█',
+                            (true, false) => '▀',
+                            (false, true) => '▄',
+                            (false, false) => ' ',
+                        }
+                    })
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+}
+
+// the word in the font of the GUI, one pixel is half a character cell
+fn rasterize(font: &FontRef, text: &str, height_px: f32) -> Bitmap {
+    let scaled = font.as_scaled(PxScale::from(height_px));
+    let ascent = scaled.ascent();
+    let height = (ascent - scaled.descent()).ceil() as usize;
+    let mut caret = 0.0;
+    let mut previous = None;
+    let mut glyphs = vec![];
+    for c in text.chars() {
+        let mut glyph = scaled.scaled_glyph(c);
+        if let Some(previous) = previous {
+            caret += scaled.kern(previous, glyph.id);
+        }
+        glyph.position = point(caret, ascent);
+        caret += scaled.h_advance(glyph.id);
+        previous = Some(glyph.id);
+        glyphs.push(glyph);
+    }
+    let width = caret.ceil() as usize;
+    let mut pixels = vec![false; width * height];
+    for glyph in glyphs {
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|x, y, coverage| {
+                let px = bounds.min.x as i64 + x as i64;
+                let py = bounds.min.y as i64 + y as i64;
+                if coverage > 0.5
+                    && (0..width as i64).contains(&px)
+                    && (0..height as i64).contains(&py)
+                {
+                    pixels[py as usize * width + px as usize] = true;
+                }
+            });
+        }
+    }
+    Bitmap {
+        width,
+        height,
+        pixels,
+    }
+}
+
+// restores the terminal also when leaving with an error
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<TerminalGuard> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct Reader {
+    conf: SettingsContainer,
+    font: Option<FontRef<'static>>,
+    running: bool,
+    poem_mode: bool,
+    word: Entry,
+    position: usize,
+    scroll: f32,
+    reloaded_at: Option<Instant>,
+    // rendered text with its pixel height, a poem is only rendered once
+    cache: Option<(String, f32, Bitmap)>,
+}
+
+impl Reader {
+    fn bitmap(&mut self, height_px: f32) -> Option<&Bitmap> {
+        let font = self.font.as_ref()?;
+        let text = &self.word.text;
+        if !matches!(&self.cache, Some((t, h, _)) if t == text && *h == height_px) {
+            self.cache = Some((text.clone(), height_px, rasterize(font, text, height_px)));
+        }
+        self.cache.as_ref().map(|(_, _, bitmap)| bitmap)
+    }
+
+    fn word_lines(&mut self, columns: usize, rows: usize) -> Vec<String> {
+        // the font size of the GUI, a quarter of it in pixels still fits most terminals
+        let mut height_px = (self.conf.font_size / 4.0).min(rows as f32 * 2.0);
+        if self.poem_mode {
+            let scroll = self.scroll;
+            return match self.bitmap(height_px) {
+                Some(bitmap) => {
+                    let cycle = (bitmap.width + columns) as i64;
+                    let start = scroll as i64 - columns as i64 / 2;
+                    bitmap.lines(columns, |c| (start + c as i64).rem_euclid(cycle))
+                }
+                None => vec![self
+                    .word
+                    .text
+                    .chars()
+                    .skip(scroll as usize)
+                    .take(columns)
+                    .collect()],
+            };
+        }
+        if let Some(width) = self.bitmap(height_px).map(|b| b.width) {
+            if width > columns {
+                height_px = (height_px * columns as f32 / width as f32).floor();
+            }
+        }
+        // too small to read as blocks
+        if height_px < 8.0 {
+            return vec![format!("{:^1$}", self.word.text, columns)];
+        }
+        match self.bitmap(height_px) {
+            Some(bitmap) => {
+                let left = (columns.saturating_sub(bitmap.width) / 2) as i64;
+                bitmap.lines(columns, |c| c as i64 - left)
+            }
+            None => vec![format!("{:^1$}", self.word.text, columns)],
+        }
+    }
+
+    fn frame(&mut self, columns: usize, rows: usize) -> Vec<String> {
+        let reloaded = self
+            .reloaded_at
+            .is_some_and(|at| at.elapsed() < Duration::from_secs(3));
+        let status = format!(
+            "{}  {:.0} {}{}   Wort {}   {}{}",
+            if self.running { "▶" } else { "⏸" },
+            self.conf.rate,
+            if self.poem_mode { "cpm" } else { "wpm" },
+            if self.conf.random && !self.poem_mode {
+                "  Random"
+            } else {
+                ""
+            },
+            self.position + 1,
+            self.conf
+                .file_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            if reloaded { "   ↻ neu geladen" } else { "" }
+        );
+        let help = "Leertaste: Start/Stopp  ←/→: Wort  ↑/↓: Frequenz (Shift ±10)  M: schwierig  R: Random  Q: Ende";
+
+        let word = self.word_lines(columns, rows.saturating_sub(4));
+        let mut lines = vec![String::new(); rows.saturating_sub(word.len() + 3) / 2];
+        lines.extend(word);
+        lines.resize(rows.saturating_sub(2), String::new());
+        lines.push(status);
+        lines.push(help.to_string());
+        lines
+            .into_iter()
+            .map(|l| l.chars().take(columns).collect())
+            .collect()
+    }
+
+    // returns false to quit
+    fn key(&mut self, key: KeyEvent, engine: &Engine, session: &mut SessionTracker) -> bool {
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        let step = if shift { 10.0 } else { 1.0 };
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char(' ') => self.running = !self.running,
+            KeyCode::Left => {
+                let _ = engine.step_tx.send(StepDir::BACKWARD);
+                session.step_backward();
+            }
+            KeyCode::Right => {
+                let _ = engine.step_tx.send(StepDir::FORWARD);
+            }
+            KeyCode::Up if self.conf.rate <= 800.0 - step => self.conf.rate += step,
+            KeyCode::Down if self.conf.rate >= 10.0 + step => self.conf.rate -= step,
+            KeyCode::Char('m') | KeyCode::Char('M') if !self.poem_mode => {
+                let word = self.word.text.clone();
+                self.conf.toggle_difficult_word(&word);
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') if !self.poem_mode => {
+                self.conf.random = !self.conf.random
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+pub fn run(
+    engine: Engine,
+    conf: SettingsContainer,
+    profiles: Profiles,
+    overrides: Overrides,
+) -> io::Result<()> {
+    // the terminal only reads, so the text is split as chosen for reading
+    let _ = engine.load_tx.send(LoadRequest {
+        path: conf.file_path.clone(),
+        position: conf.idx.max(0) as usize,
+        mode: conf.mode,
+        word: None,
+    });
+
+    let mut reader = Reader {
+        conf,
+        font: FontRef::try_from_slice(FONT).ok(),
+        running: false,
+        poem_mode: false,
+        word: Entry::new(""),
+        position: 0,
+        scroll: 0.0,
+        reloaded_at: None,
+        cache: None,
+    };
+    let mut watch = FileWatch::new(&reader.conf.file_path);
+    let mut history = History::load_for(&profiles.active);
+    let mut session = SessionTracker::new();
+    let mut words_shown = 0;
+    let mut last_frame = vec![];
+    let mut last_instant = Instant::now();
+
+    let guard = TerminalGuard::enter()?;
+    let mut stdout = io::stdout();
+    loop {
+        // the background thread signals every word, the terminal is redrawn anyway
+        while engine.sync_rx.try_recv().is_ok() {}
+        if let Ok(progress) = engine.progress_lock.read() {
+            session.words_shown(progress.words_shown - words_shown);
+            words_shown = progress.words_shown;
+            reader.position = progress.position;
+        }
+        if let Ok(word) = engine.word_lock.read() {
+            reader.word = word.clone();
+        }
+        if let Ok(poem_mode) = engine.mode_lock.read() {
+            reader.poem_mode = *poem_mode;
+        }
+        reader.conf.idx = reader.position as isize;
+
+        if watch.changed() {
+            let _ = engine.load_tx.send(LoadRequest {
+                path: reader.conf.file_path.clone(),
+                position: reader.position,
+                mode: reader.conf.mode,
+                word: Some(reader.word.text.clone()),
+            });
+            reader.reloaded_at = Some(Instant::now());
+        }
+
+        // same scrolling speed in characters per second as in the window
+        let now = Instant::now();
+        if reader.poem_mode && reader.running {
+            let height_px = reader.conf.font_size / 4.0;
+            reader.scroll += reader.conf.rate * height_px / reader.conf.font_size
+                * now.duration_since(last_instant).as_secs_f32();
+        }
+        last_instant = now;
+
+        let (columns, rows) = terminal::size()?;
+        let frame = reader.frame(columns as usize, rows as usize);
+        if frame != last_frame {
+            queue!(stdout, terminal::Clear(terminal::ClearType::All))?;
+            for (row, line) in frame.iter().enumerate() {
+                queue!(stdout, cursor::MoveTo(0, row as u16), style::Print(line))?;
+            }
+            stdout.flush()?;
+            last_frame = frame;
+        }
+
+        if event::poll(Duration::from_millis(20))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Release && !reader.key(key, &engine, &mut session) {
+                    break;
+                }
+            }
+        }
+
+        if let Ok(mut write_guard) = engine.rate_lock.write() {
+            *write_guard = reader.conf.rate;
+        }
+        if let Ok(mut write_guard) = engine.running_lock.write() {
+            *write_guard = reader.running;
+        }
+        if let Ok(mut write_guard) = engine.random_lock.write() {
+            *write_guard = reader.conf.random;
+        }
+        session.set_running(reader.running);
+    }
+    drop(guard);
+
+    if let Ok(mut write_guard) = engine.running_lock.write() {
+        *write_guard = false;
+    }
+    if let Some(s) = session.finish(&profiles.active, &reader.conf.file_path) {
+        history.sessions.push(s);
+        history.store_for(&profiles.active);
+    }
+    save_settings(&profiles.active, &overrides.persisted(&reader.conf));
+    overrides.persisted_profiles(&profiles).store();
+    Ok(())
+}
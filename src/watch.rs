@@ -0,0 +1,40 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+// notices when the open file is saved from another program, e.g. a text editor
+pub struct FileWatch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl FileWatch {
+    pub fn new(path: &Path) -> FileWatch {
+        FileWatch {
+            path: path.to_path_buf(),
+            modified: modified(path),
+            last_check: Instant::now(),
+        }
+    }
+
+    // true once after every change, the file is looked at once a second
+    pub fn changed(&mut self) -> bool {
+        if self.last_check.elapsed() < CHECK_INTERVAL {
+            return false;
+        }
+        self.last_check = Instant::now();
+        let modified = modified(&self.path);
+        // a file that is missing for a moment while it is saved is not a change
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+}